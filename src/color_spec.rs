@@ -0,0 +1,224 @@
+//! Conversions between [`Style`] and the `ColorSpec` of the termcolor crate.
+//!
+//! A `ColorSpec` has fewer attributes than a `Style`, so the conversion to it is lossy:
+//! - `hidden`, `underline_color`, `underline_style` and `explicit_defaults` are dropped,
+//!   any underline is written as a straight one
+//! - `dim` becomes `set_dimmed`, which termcolor only writes on ANSI terminals
+//! - `Color::HEX` and `Color::RGBA` become `Rgb`, with the alpha composited over black,
+//!   and an invalid hexadecimal code leaves the color unset
+//!
+//! The other way, `strikethrough` and `reset` are dropped, and a 256 color code stays a `Color256`
+//! even for the system colors.
+
+use termcolor::{Color as SpecColor, ColorSpec};
+
+use crate::{Color, ColorError, Style};
+
+// the base colors of termcolor, in the order of `Color::SYSTEM_COLORS`
+const BASE_COLORS: [SpecColor; 8] = [
+    SpecColor::Black,
+    SpecColor::Red,
+    SpecColor::Green,
+    SpecColor::Yellow,
+    SpecColor::Blue,
+    SpecColor::Magenta,
+    SpecColor::Cyan,
+    SpecColor::White,
+];
+
+/// Returns the position of a predefined color in `Color::SYSTEM_COLORS`.
+fn system_index(color: Color) -> Option<usize> {
+    Color::SYSTEM_COLORS
+        .iter()
+        .position(|&system| system == color)
+}
+
+/// Converts a color, writing the bright colors as intense ones when `intense` is set.
+fn to_spec_color(color: Color, intense: bool) -> Option<SpecColor> {
+    if let Some(index) = system_index(color) {
+        return Some(match (index, intense) {
+            (0..=7, false) => BASE_COLORS[index],
+            (8..=15, true) => BASE_COLORS[index - 8],
+            // the intensity is shared by both colors, so a mixed pair uses the palette codes
+            _ => SpecColor::Ansi256(index as u8),
+        });
+    }
+    match color {
+        Color::Empty => None,
+        Color::Color256(code) => Some(SpecColor::Ansi256(code)),
+        _ => color
+            .to_rgb_tuple()
+            .map(|(r, g, b)| SpecColor::Rgb(r, g, b)),
+    }
+}
+
+/// Converts a color of a `ColorSpec`, reading the base colors as bright ones when `intense` is set.
+fn from_spec_color(color: &SpecColor, intense: bool) -> Result<Color, ColorError> {
+    if let Some(index) = BASE_COLORS.iter().position(|base| base == color) {
+        let offset = if intense { 8 } else { 0 };
+        return Ok(Color::SYSTEM_COLORS[index + offset]);
+    }
+    match *color {
+        SpecColor::Ansi256(code) => Ok(Color::Color256(code)),
+        SpecColor::Rgb(r, g, b) => Ok(Color::RGB(r, g, b)),
+        _ => Err(ColorError::conversion(
+            &format!("{:?}", color),
+            "Color",
+            "the termcolor color is unknown",
+        )),
+    }
+}
+
+/// Builds a `ColorSpec` with the colors and the attributes termcolor supports.
+///
+/// The bright colors are written as intense base colors, unless the other color is a
+/// non-bright base color, as the intensity applies to both.
+/// See the [module documentation](self) for the attributes that are dropped.
+impl From<Style> for ColorSpec {
+    fn from(style: Style) -> Self {
+        let is_bright = |color| matches!(system_index(color), Some(8..=15));
+        let is_base = |color| matches!(system_index(color), Some(0..=7));
+        let intense = (is_bright(style.foreground) || is_bright(style.background))
+            && !is_base(style.foreground)
+            && !is_base(style.background);
+
+        let mut spec = ColorSpec::new();
+        spec.set_fg(to_spec_color(style.foreground, intense))
+            .set_bg(to_spec_color(style.background, intense))
+            .set_intense(intense)
+            .set_bold(style.bold)
+            .set_dimmed(style.dim)
+            .set_italic(style.italic)
+            .set_underline(style.underline);
+        spec
+    }
+}
+
+/// Reads the colors and the attributes of a `ColorSpec`, with intense base colors as the bright ones.
+///
+/// # Errors
+///
+/// Returns `ColorError::InvalidColorValue` for a color added to termcolor after this conversion.
+impl TryFrom<&ColorSpec> for Style {
+    type Error = ColorError;
+
+    fn try_from(spec: &ColorSpec) -> Result<Self, Self::Error> {
+        let color = |color: Option<&SpecColor>| {
+            color.map_or(Ok(Color::Empty), |color| {
+                from_spec_color(color, spec.intense())
+            })
+        };
+        Ok(Style {
+            foreground: color(spec.fg())?,
+            background: color(spec.bg())?,
+            bold: spec.bold(),
+            dim: spec.dimmed(),
+            italic: spec.italic(),
+            underline: spec.underline(),
+            ..Style::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use termcolor::{Color as SpecColor, ColorSpec};
+
+    use crate::{Color, Style, UnderlineStyle};
+
+    #[test]
+    fn test_style_to_color_spec() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 128, 0))
+            .background(Color::Color256(236))
+            .bold()
+            .italic()
+            .underline()
+            .build();
+        let spec = ColorSpec::from(style);
+        assert_eq!(spec.fg(), Some(&SpecColor::Rgb(255, 128, 0)));
+        assert_eq!(spec.bg(), Some(&SpecColor::Ansi256(236)));
+        assert!(spec.bold() && spec.italic() && spec.underline());
+        assert!(!spec.intense() && !spec.dimmed());
+
+        assert_eq!(
+            ColorSpec::from(Style::builder().foreground(Color::HEX("#00ff00")).build()).fg(),
+            Some(&SpecColor::Rgb(0, 255, 0))
+        );
+        assert_eq!(ColorSpec::from(Style::default()).fg(), None);
+    }
+
+    #[test]
+    fn test_bright_colors() {
+        let spec = ColorSpec::from(
+            Style::builder()
+                .foreground(Color::BrightRed)
+                .background(Color::BrightBlack)
+                .build(),
+        );
+        assert!(spec.intense());
+        assert_eq!(spec.fg(), Some(&SpecColor::Red));
+        assert_eq!(spec.bg(), Some(&SpecColor::Black));
+
+        // a base color next to a bright one keeps the spec from being intense
+        let spec = ColorSpec::from(
+            Style::builder()
+                .foreground(Color::BrightRed)
+                .background(Color::Blue)
+                .build(),
+        );
+        assert!(!spec.intense());
+        assert_eq!(spec.fg(), Some(&SpecColor::Ansi256(9)));
+        assert_eq!(spec.bg(), Some(&SpecColor::Blue));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let styles = [
+            Style::builder()
+                .foreground(Color::BrightCyan)
+                .bold()
+                .build(),
+            Style::builder()
+                .foreground(Color::Green)
+                .background(Color::RGB(10, 20, 30))
+                .dim()
+                .underline()
+                .build(),
+            Style::builder()
+                .foreground(Color::Color256(208))
+                .italic()
+                .build(),
+        ];
+        for style in styles {
+            let spec = ColorSpec::from(style);
+            assert_eq!(Style::try_from(&spec).unwrap(), style);
+        }
+    }
+
+    #[test]
+    fn test_lossy_attributes() {
+        let style = Style::builder()
+            .foreground(Color::RGBA(255, 0, 0, 128))
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Red)
+            .hidden()
+            .build();
+        let back = Style::try_from(&ColorSpec::from(style)).unwrap();
+        assert_eq!(
+            back,
+            Style::builder()
+                .foreground(Color::RGB(128, 0, 0))
+                .underline()
+                .build()
+        );
+
+        let mut spec = ColorSpec::new();
+        spec.set_strikethrough(true).set_fg(Some(SpecColor::Yellow));
+        assert_eq!(
+            Style::try_from(&spec).unwrap(),
+            Style::builder().foreground(Color::Yellow).build()
+        );
+    }
+}
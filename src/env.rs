@@ -0,0 +1,911 @@
+//! Detection of the terminal's color support from the environment.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, IsTerminal};
+
+use crate::ColorSupport;
+
+/// Check the level of color support of the terminal
+///
+/// The level is detected from the commonly used environment variables:
+///
+/// - `NO_COLOR` (non-empty) disables colors, even when forced (see [`check_color_support_with`]).
+/// - `CLICOLOR_FORCE` and `FORCE_COLOR` force colors on, `FORCE_COLOR` may also pick the level
+///   (`1` basic, `2` 256 colors, `3` true color).
+/// - `COLORTERM` set to `truecolor` or `24bit` enables true color.
+/// - `TERM` is checked for 256 color and true color capable terminals.
+///
+/// When `TERM` is not set, the console itself is queried.
+/// On Windows 10 and later this enables virtual terminal processing, which supports true color.
+///
+/// Colors are disabled when stdout is not a terminal (e.g. piped to a file),
+/// unless they are forced with `CLICOLOR_FORCE` or `FORCE_COLOR`.
+/// Use [`check_color_support_for`] to check stderr instead.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{check_color_support, ColorSupport};
+///
+/// match check_color_support() {
+///     ColorSupport::TrueColor => println!("24-bit colors"),
+///     ColorSupport::Color256 => println!("256 colors"),
+///     ColorSupport::Basic => println!("16 colors"),
+///     ColorSupport::NoColor => println!("no colors"),
+/// }
+/// ```
+pub fn check_color_support() -> ColorSupport {
+    check_color_support_for(Stream::Stdout)
+}
+
+/// The output stream colored text is written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    /// Returns `true` if the stream is connected to a terminal.
+    fn is_terminal(self) -> bool {
+        match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// Check the level of color support of the terminal connected to the given stream
+///
+/// This works like [`check_color_support`], but checks whether `stream` is a terminal instead of stdout.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{check_color_support_for, Stream};
+///
+/// let support = check_color_support_for(Stream::Stderr);
+/// eprintln!("stderr color support: {:?}", support);
+/// ```
+pub fn check_color_support_for(stream: Stream) -> ColorSupport {
+    detect_support(
+        &|name| env::var(name).ok(),
+        ColorPrecedence::default(),
+        stream.is_terminal(),
+        || console_support(stream),
+    )
+}
+
+/// Which of `NO_COLOR` and the variables forcing colors wins when both are set
+///
+/// The conventions do not agree: `NO_COLOR` asks to disable colors whatever else is set,
+/// while `CLICOLOR_FORCE` and `FORCE_COLOR` ask to enable them whatever else is set.
+/// `NO_COLOR` wins by default, as it is usually set once by the user for every program,
+/// while forcing is often left on by tools such as CI runners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorPrecedence {
+    /// `NO_COLOR` disables colors even when they are forced.
+    #[default]
+    NoColorWins,
+
+    /// `CLICOLOR_FORCE` and `FORCE_COLOR` enable colors even when `NO_COLOR` is set.
+    ForceWins,
+}
+
+/// Check the level of color support of stdout, with the given precedence between `NO_COLOR` and forcing
+///
+/// This works like [`check_color_support`], which uses `ColorPrecedence::NoColorWins`.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{check_color_support_with, ColorPrecedence};
+///
+/// let support = check_color_support_with(ColorPrecedence::ForceWins);
+/// println!("color support: {:?}", support);
+/// ```
+pub fn check_color_support_with(precedence: ColorPrecedence) -> ColorSupport {
+    detect_support(
+        &|name| env::var(name).ok(),
+        precedence,
+        Stream::Stdout.is_terminal(),
+        || console_support(Stream::Stdout),
+    )
+}
+
+/// Check if colored output is enabled for stdout
+///
+/// This is a boolean shorthand for [`check_color_support`], returning `false` for `ColorSupport::NoColor`.
+///
+/// # Example
+///
+/// ```
+/// use inksac::color_enabled;
+///
+/// if color_enabled() {
+///     println!("colors are enabled");
+/// }
+/// ```
+pub fn color_enabled() -> bool {
+    color_enabled_for(Stream::Stdout)
+}
+
+/// Check if colored output is enabled for the given stream
+///
+/// This is a boolean shorthand for [`check_color_support_for`], returning `false` for `ColorSupport::NoColor`.
+pub fn color_enabled_for(stream: Stream) -> bool {
+    check_color_support_for(stream).has_color()
+}
+
+/// Whether the terminal background is dark or light
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Background {
+    Dark,
+    Light,
+}
+
+impl Background {
+    /// Returns `dark` on a dark background and `light` on a light one.
+    pub fn select<T>(self, dark: T, light: T) -> T {
+        match self {
+            Background::Dark => dark,
+            Background::Light => light,
+        }
+    }
+}
+
+/// Detect whether the terminal background is dark or light
+///
+/// The background is read from the `COLORFGBG` environment variable set by some terminals
+/// (e.g. rxvt, Konsole), where `15;0` means a white foreground on a black background.
+/// Returns `None` when the variable is not set or does not name a background color.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{detect_terminal_background, Background};
+///
+/// match detect_terminal_background() {
+///     Some(Background::Light) => println!("light background"),
+///     Some(Background::Dark) => println!("dark background"),
+///     None => println!("unknown background"),
+/// }
+/// ```
+pub fn detect_terminal_background() -> Option<Background> {
+    env::var("COLORFGBG")
+        .ok()
+        .as_deref()
+        .and_then(parse_colorfgbg)
+}
+
+/// Pick the dark or light variant of a theme for the terminal background
+///
+/// Most terminals use a dark background, so `dark` is picked when the background can not be detected.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{select_theme, Color};
+///
+/// let accent = select_theme(Color::BrightYellow, Color::Blue);
+/// ```
+pub fn select_theme<T>(dark: T, light: T) -> T {
+    detect_terminal_background()
+        .unwrap_or(Background::Dark)
+        .select(dark, light)
+}
+
+/// Reads the background from a `COLORFGBG` value, `fg;bg` or `fg;default;bg`.
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+
+    // the dark colors of the 16 color palette, plus the bright black (dark gray)
+    match bg {
+        0..=6 | 8 => Some(Background::Dark),
+        7 | 9..=15 => Some(Background::Light),
+        _ => None,
+    }
+}
+
+/// Get the number of columns of the terminal
+///
+/// See [`terminal_size`] for how the size is detected.
+///
+/// # Example
+///
+/// ```
+/// use inksac::terminal_width;
+///
+/// let width = terminal_width().unwrap_or(80);
+/// println!("{}", "-".repeat(width as usize));
+/// ```
+pub fn terminal_width() -> Option<u16> {
+    env_dimension("COLUMNS").or_else(|| os_terminal_size().map(|(width, _)| width))
+}
+
+/// Get the size of the terminal as `(columns, lines)`
+///
+/// The `COLUMNS` and `LINES` environment variables are read. With the `terminal-size` feature,
+/// the terminal connected to stdout is also queried on unix (Linux, Android and macOS) and Windows
+/// when they are not set.
+/// Returns `None` when the size can not be detected, e.g. when stdout is piped to a file.
+pub fn terminal_size() -> Option<(u16, u16)> {
+    let width = env_dimension("COLUMNS");
+    let height = env_dimension("LINES");
+    let os_size = if width.is_none() || height.is_none() {
+        os_terminal_size()
+    } else {
+        None
+    };
+
+    Some((
+        width.or(os_size.map(|(width, _)| width))?,
+        height.or(os_size.map(|(_, height)| height))?,
+    ))
+}
+
+/// Reads a positive terminal dimension from an environment variable.
+fn env_dimension(name: &str) -> Option<u16> {
+    env::var(name)
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&value| value > 0)
+}
+
+/// Queries the size of the terminal connected to stdout.
+#[cfg(feature = "terminal-size")]
+fn os_terminal_size() -> Option<(u16, u16)> {
+    #[cfg(windows)]
+    return windows::console_size(Stream::Stdout);
+
+    #[cfg(unix)]
+    return unix::terminal_size(Stream::Stdout);
+
+    #[cfg(not(any(windows, unix)))]
+    None
+}
+
+/// Querying the terminal needs the `terminal-size` feature, only the environment is read without it.
+#[cfg(not(feature = "terminal-size"))]
+fn os_terminal_size() -> Option<(u16, u16)> {
+    None
+}
+
+/// Color support of the console itself, used when `TERM` is not set.
+#[cfg_attr(not(windows), allow(unused_variables))]
+fn console_support(stream: Stream) -> ColorSupport {
+    #[cfg(windows)]
+    if windows::enable_virtual_terminal(stream) {
+        return ColorSupport::TrueColor;
+    }
+
+    ColorSupport::NoColor
+}
+
+impl ColorSupport {
+    /// Detects the color support from the given environment variables, without reading the process environment.
+    ///
+    /// The variables are read like [`check_color_support`] does, as if the output was a terminal.
+    /// Without `TERM`, the console is not queried and there is no color support.
+    /// This makes the detection deterministic, e.g. to test how a program behaves in various terminals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use inksac::ColorSupport;
+    ///
+    /// let vars = HashMap::from([
+    ///     ("TERM".to_string(), "xterm-256color".to_string()),
+    ///     ("COLORTERM".to_string(), "truecolor".to_string()),
+    /// ]);
+    /// assert_eq!(ColorSupport::detect_from(&vars), ColorSupport::TrueColor);
+    /// ```
+    pub fn detect_from(vars: &HashMap<String, String>) -> ColorSupport {
+        detect_support(
+            &|name| vars.get(name).cloned(),
+            ColorPrecedence::default(),
+            true,
+            || ColorSupport::NoColor,
+        )
+    }
+}
+
+/// Detects the color support from the variables returned by `var`.
+fn detect_support(
+    var: &dyn Fn(&str) -> Option<String>,
+    precedence: ColorPrecedence,
+    is_terminal: bool,
+    console: impl FnOnce() -> ColorSupport,
+) -> ColorSupport {
+    let forced = forced_support(var);
+
+    if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        let force_wins = precedence == ColorPrecedence::ForceWins
+            && forced.is_some_and(|level| level != ColorSupport::NoColor);
+        if !force_wins {
+            return ColorSupport::NoColor;
+        }
+    }
+
+    match forced {
+        Some(ColorSupport::NoColor) => return ColorSupport::NoColor,
+        None if !is_terminal => return ColorSupport::NoColor,
+        _ => {}
+    }
+
+    // dumb terminals (e.g. the Emacs shell) can not display any styling,
+    // even if `COLORTERM` claims otherwise, so only forcing enables it
+    if var("TERM").as_deref() == Some("dumb") {
+        return forced.unwrap_or(ColorSupport::NoColor);
+    }
+
+    let colorterm = var("COLORTERM").unwrap_or_default();
+    let detected = if colorterm == "truecolor" || colorterm == "24bit" {
+        ColorSupport::TrueColor
+    } else {
+        match var("TERM") {
+            Some(term) => term_support(&term),
+            None => console(),
+        }
+    };
+
+    detected.max(forced.unwrap_or(ColorSupport::NoColor))
+}
+
+/// Reads the support level forced by `CLICOLOR_FORCE` or `FORCE_COLOR`.
+fn forced_support(var: &dyn Fn(&str) -> Option<String>) -> Option<ColorSupport> {
+    if let Some(level) = var("FORCE_COLOR") {
+        return Some(match level.as_str() {
+            "0" | "false" => ColorSupport::NoColor,
+            "2" => ColorSupport::Color256,
+            "3" => ColorSupport::TrueColor,
+            _ => ColorSupport::Basic,
+        });
+    }
+
+    var("CLICOLOR_FORCE")
+        .filter(|value| !value.is_empty() && value != "0")
+        .map(|_| ColorSupport::Basic)
+}
+
+/// Maps the value of `TERM` to its color support.
+///
+/// The multiplexers (tmux and screen) always support 256 colors, but only pass true colors
+/// through when configured to, in which case tmux sets `COLORTERM=truecolor` as well.
+fn term_support(term: &str) -> ColorSupport {
+    if term.is_empty() || term == "dumb" {
+        ColorSupport::NoColor
+    } else if term.starts_with("tmux") || term.starts_with("screen") {
+        ColorSupport::Color256
+    } else if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
+        ColorSupport::TrueColor
+    } else if term.contains("256color") {
+        ColorSupport::Color256
+    } else {
+        ColorSupport::Basic
+    }
+}
+
+#[cfg(all(unix, feature = "terminal-size"))]
+mod unix {
+    //! Minimal bindings to the terminal size query.
+
+    use std::ffi::{c_int, c_ushort};
+
+    // musl and bionic declare the request as an `int`, glibc and macOS as an `unsigned long`
+    #[cfg(any(target_env = "musl", target_os = "android"))]
+    type Request = c_int;
+    #[cfg(not(any(target_env = "musl", target_os = "android")))]
+    type Request = std::ffi::c_ulong;
+
+    use super::Stream;
+
+    #[repr(C)]
+    struct WinSize {
+        rows: c_ushort,
+        columns: c_ushort,
+        x_pixels: c_ushort,
+        y_pixels: c_ushort,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: Option<Request> = if cfg!(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    )) {
+        None
+    } else {
+        Some(0x5413)
+    };
+
+    #[cfg(target_os = "macos")]
+    const TIOCGWINSZ: Option<Request> = Some(0x4008_7468);
+
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+    const TIOCGWINSZ: Option<Request> = None;
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: Request, ...) -> c_int;
+    }
+
+    /// Returns the `(columns, rows)` of the terminal connected to the given stream.
+    ///
+    /// Returns `None` when the stream is not a terminal, or the platform is not supported.
+    pub(super) fn terminal_size(stream: Stream) -> Option<(u16, u16)> {
+        let request = TIOCGWINSZ?;
+        let fd = match stream {
+            Stream::Stdout => 1,
+            Stream::Stderr => 2,
+        };
+
+        let mut size = WinSize {
+            rows: 0,
+            columns: 0,
+            x_pixels: 0,
+            y_pixels: 0,
+        };
+        // SAFETY: `size` is a valid `winsize` for the kernel to write to
+        if unsafe { ioctl(fd, request, &mut size) } != 0 || size.columns == 0 || size.rows == 0 {
+            return None;
+        }
+        Some((size.columns, size.rows))
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    //! Minimal bindings to the Windows console API.
+
+    use std::ffi::c_void;
+
+    use super::Stream;
+
+    type Handle = *mut c_void;
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+    const INVALID_HANDLE_VALUE: Handle = -1isize as Handle;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[cfg(feature = "terminal-size")]
+    #[repr(C)]
+    struct Coord {
+        x: i16,
+        y: i16,
+    }
+
+    #[cfg(feature = "terminal-size")]
+    #[repr(C)]
+    struct SmallRect {
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    }
+
+    #[cfg(feature = "terminal-size")]
+    #[repr(C)]
+    struct ConsoleScreenBufferInfo {
+        size: Coord,
+        cursor_position: Coord,
+        attributes: u16,
+        window: SmallRect,
+        maximum_window_size: Coord,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+        #[cfg(feature = "terminal-size")]
+        fn GetConsoleScreenBufferInfo(console: Handle, info: *mut ConsoleScreenBufferInfo) -> i32;
+    }
+
+    /// Returns the handle of the given stream, or `None` if it has none.
+    fn std_handle(stream: Stream) -> Option<Handle> {
+        let std_handle = match stream {
+            Stream::Stdout => STD_OUTPUT_HANDLE,
+            Stream::Stderr => STD_ERROR_HANDLE,
+        };
+
+        // SAFETY: `GetStdHandle` has no preconditions
+        let handle = unsafe { GetStdHandle(std_handle) };
+        if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        Some(handle)
+    }
+
+    /// Returns the `(columns, rows)` of the visible window of the console of the given stream.
+    #[cfg(feature = "terminal-size")]
+    pub(super) fn console_size(stream: Stream) -> Option<(u16, u16)> {
+        let handle = std_handle(stream)?;
+        let mut info = ConsoleScreenBufferInfo {
+            size: Coord { x: 0, y: 0 },
+            cursor_position: Coord { x: 0, y: 0 },
+            attributes: 0,
+            window: SmallRect {
+                left: 0,
+                top: 0,
+                right: 0,
+                bottom: 0,
+            },
+            maximum_window_size: Coord { x: 0, y: 0 },
+        };
+
+        // SAFETY: the handle is valid, and `info` is a valid pointer for the call
+        if unsafe { GetConsoleScreenBufferInfo(handle, &mut info) } == 0 {
+            return None;
+        }
+
+        let columns = info.window.right - info.window.left + 1;
+        let rows = info.window.bottom - info.window.top + 1;
+        Some((u16::try_from(columns).ok()?, u16::try_from(rows).ok()?))
+    }
+
+    /// Enables virtual terminal processing on the console of the given stream.
+    ///
+    /// Returns `false` when the stream is not a console, or the console does not support it (before Windows 10).
+    pub(super) fn enable_virtual_terminal(stream: Stream) -> bool {
+        let Some(handle) = std_handle(stream) else {
+            return false;
+        };
+
+        // SAFETY: the handle is valid, and `mode` is a valid pointer for the call
+        unsafe {
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                return false;
+            }
+            if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+                return true;
+            }
+
+            SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes the tests that modify the process environment.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Runs `f` with the given environment variables set (`Some`) or removed (`None`),
+    /// restoring the previous values afterwards.
+    pub(crate) fn run_with_env_vars<T>(vars: &[(&str, Option<&str>)], f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+        let previous: Vec<_> = vars
+            .iter()
+            .map(|(name, _)| (*name, env::var_os(name)))
+            .collect();
+        for (name, value) in vars {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+
+        let result = f();
+
+        for (name, value) in previous {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+        result
+    }
+
+    fn lookup<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    fn detect(vars: &[(&str, &str)]) -> ColorSupport {
+        detect_with_console(vars, || ColorSupport::NoColor)
+    }
+
+    fn detect_with_console(vars: &[(&str, &str)], console: fn() -> ColorSupport) -> ColorSupport {
+        detect_support(&lookup(vars), ColorPrecedence::default(), true, console)
+    }
+
+    fn detect_piped(vars: &[(&str, &str)]) -> ColorSupport {
+        detect_support(&lookup(vars), ColorPrecedence::default(), false, || {
+            ColorSupport::TrueColor
+        })
+    }
+
+    #[test]
+    fn test_term_detection() {
+        assert_eq!(detect(&[("TERM", "xterm")]), ColorSupport::Basic);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")]),
+            ColorSupport::Color256
+        );
+        assert_eq!(detect(&[("TERM", "xterm-direct")]), ColorSupport::TrueColor);
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("COLORTERM", "truecolor")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(&[]), ColorSupport::NoColor);
+    }
+
+    #[test]
+    fn test_terminal_size_from_env() {
+        run_with_env_vars(&[("COLUMNS", Some("120")), ("LINES", Some("40"))], || {
+            assert_eq!(terminal_width(), Some(120));
+            assert_eq!(terminal_size(), Some((120, 40)));
+        });
+        run_with_env_vars(&[("COLUMNS", Some(" 90\n"))], || {
+            assert_eq!(terminal_width(), Some(90));
+        });
+
+        // invalid values fall back to the terminal, which the test output is not
+        run_with_env_vars(&[("COLUMNS", Some("0")), ("LINES", Some("wide"))], || {
+            if !io::stdout().is_terminal() {
+                assert_eq!(terminal_width(), None);
+                assert_eq!(terminal_size(), None);
+            }
+        });
+    }
+
+    #[test]
+    fn test_detect_from() {
+        let detect_from = |vars: &[(&str, &str)]| {
+            let vars = vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            ColorSupport::detect_from(&vars)
+        };
+
+        assert_eq!(detect_from(&[]), ColorSupport::NoColor);
+        assert_eq!(detect_from(&[("TERM", "xterm")]), ColorSupport::Basic);
+        assert_eq!(detect_from(&[("TERM", "tmux")]), ColorSupport::Color256);
+        assert_eq!(
+            detect_from(&[("TERM", "xterm"), ("COLORTERM", "24bit")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect_from(&[("TERM", "xterm-direct"), ("NO_COLOR", "1")]),
+            ColorSupport::NoColor
+        );
+        assert_eq!(detect_from(&[("FORCE_COLOR", "2")]), ColorSupport::Color256);
+
+        // the process environment is not read
+        run_with_env_vars(&[("TERM", Some("xterm-256color"))], || {
+            assert_eq!(detect_from(&[]), ColorSupport::NoColor);
+        });
+    }
+
+    #[test]
+    fn test_color_precedence() {
+        let detect_with = |precedence, vars: &[(&str, &str)]| {
+            detect_support(&lookup(vars), precedence, true, || ColorSupport::NoColor)
+        };
+        let both = [
+            ("TERM", "xterm"),
+            ("NO_COLOR", "1"),
+            ("CLICOLOR_FORCE", "1"),
+        ];
+
+        assert_eq!(
+            detect_with(ColorPrecedence::NoColorWins, &both),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect_with(ColorPrecedence::ForceWins, &both),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            detect_with(
+                ColorPrecedence::ForceWins,
+                &[("NO_COLOR", "1"), ("FORCE_COLOR", "3")]
+            ),
+            ColorSupport::TrueColor
+        );
+
+        // without forcing, or forcing colors off, `NO_COLOR` still applies
+        for vars in [
+            &[("TERM", "xterm"), ("NO_COLOR", "1")][..],
+            &[("NO_COLOR", "1"), ("FORCE_COLOR", "0")][..],
+        ] {
+            assert_eq!(
+                detect_with(ColorPrecedence::ForceWins, vars),
+                ColorSupport::NoColor
+            );
+        }
+
+        run_with_env_vars(
+            &[
+                ("NO_COLOR", Some("1")),
+                ("CLICOLOR_FORCE", Some("1")),
+                ("FORCE_COLOR", None),
+            ],
+            || {
+                assert_eq!(
+                    check_color_support_with(ColorPrecedence::NoColorWins),
+                    ColorSupport::NoColor
+                );
+                assert_ne!(
+                    check_color_support_with(ColorPrecedence::ForceWins),
+                    ColorSupport::NoColor
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_multiplexers() {
+        assert_eq!(detect(&[("TERM", "tmux")]), ColorSupport::Color256);
+        assert_eq!(detect(&[("TERM", "screen")]), ColorSupport::Color256);
+        assert_eq!(
+            detect(&[("TERM", "screen-truecolor")]),
+            ColorSupport::Color256
+        );
+        assert_eq!(
+            detect(&[("TERM", "tmux"), ("COLORTERM", "truecolor")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "tmux-256color"), ("COLORTERM", "24bit")]),
+            ColorSupport::TrueColor
+        );
+    }
+
+    #[test]
+    fn test_no_color_and_force() {
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("NO_COLOR", "")]),
+            ColorSupport::Basic
+        );
+        assert_eq!(detect(&[("CLICOLOR_FORCE", "1")]), ColorSupport::Basic);
+        assert_eq!(detect(&[("FORCE_COLOR", "3")]), ColorSupport::TrueColor);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("FORCE_COLOR", "0")]),
+            ColorSupport::NoColor
+        );
+    }
+
+    #[test]
+    fn test_colorfgbg() {
+        assert_eq!(parse_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("12;default;8"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("0;default;7"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("15;default"), None);
+        assert_eq!(parse_colorfgbg("0;42"), None);
+        assert_eq!(parse_colorfgbg(""), None);
+
+        run_with_env_vars(&[("COLORFGBG", Some("0;15"))], || {
+            assert_eq!(detect_terminal_background(), Some(Background::Light));
+            assert_eq!(select_theme("dark", "light"), "light");
+        });
+        run_with_env_vars(&[("COLORFGBG", None)], || {
+            assert_eq!(detect_terminal_background(), None);
+            assert_eq!(select_theme("dark", "light"), "dark");
+        });
+    }
+
+    #[test]
+    fn test_dumb_terminal() {
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorSupport::NoColor);
+        assert_eq!(
+            detect(&[("TERM", "dumb"), ("COLORTERM", "truecolor")]),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")]),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            detect(&[
+                ("TERM", "dumb"),
+                ("COLORTERM", "truecolor"),
+                ("FORCE_COLOR", "2")
+            ]),
+            ColorSupport::Color256
+        );
+    }
+
+    #[test]
+    fn test_console_fallback() {
+        assert_eq!(
+            detect_with_console(&[], || ColorSupport::TrueColor),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect_with_console(&[("TERM", "xterm")], || ColorSupport::TrueColor),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            detect_with_console(&[("NO_COLOR", "1")], || ColorSupport::TrueColor),
+            ColorSupport::NoColor
+        );
+    }
+
+    #[test]
+    fn test_not_a_terminal() {
+        assert_eq!(
+            detect_piped(&[("TERM", "xterm-256color")]),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect_piped(&[("COLORTERM", "truecolor")]),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect_piped(&[("TERM", "xterm-256color"), ("CLICOLOR_FORCE", "1")]),
+            ColorSupport::Color256
+        );
+        assert_eq!(
+            detect_piped(&[("TERM", "xterm"), ("FORCE_COLOR", "1")]),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            detect_piped(&[("TERM", "xterm"), ("CLICOLOR_FORCE", "0")]),
+            ColorSupport::NoColor
+        );
+    }
+
+    #[test]
+    fn test_color_enabled() {
+        let forced = [
+            ("NO_COLOR", None),
+            ("FORCE_COLOR", Some("1")),
+            ("TERM", Some("xterm")),
+        ];
+        run_with_env_vars(&forced, || {
+            assert!(color_enabled());
+            assert!(color_enabled_for(Stream::Stdout));
+            assert!(color_enabled_for(Stream::Stderr));
+        });
+
+        let disabled = [
+            ("NO_COLOR", Some("1")),
+            ("FORCE_COLOR", Some("1")),
+            ("TERM", Some("xterm")),
+        ];
+        run_with_env_vars(&disabled, || {
+            assert!(!color_enabled());
+            assert!(!color_enabled_for(Stream::Stdout));
+            assert!(!color_enabled_for(Stream::Stderr));
+        });
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_console_support() {
+        // the streams are not always a console when running tests, so only check consistency
+        for stream in [Stream::Stdout, Stream::Stderr] {
+            let enabled = windows::enable_virtual_terminal(stream);
+            let expected = if enabled {
+                ColorSupport::TrueColor
+            } else {
+                ColorSupport::NoColor
+            };
+            assert_eq!(console_support(stream), expected);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Color gradients built from multiple color stops.
+
+use crate::{Color, ColorError};
+
+/// A color gradient made of color stops
+///
+/// Every stop has a position in `0.0..=1.0`, and the colors between two stops are interpolated in RGB,
+/// or in OKLCH with the `oklch` feature (see [`Color::interpolate_oklch`]).
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Gradient};
+///
+/// let gradient = Gradient::with_positions(vec![
+///     (0.0, Color::RGB(0, 0, 0)),
+///     (0.25, Color::RGB(255, 255, 255)),
+///     (1.0, Color::RGB(0, 0, 0)),
+/// ])
+/// .unwrap();
+///
+/// assert_eq!(gradient.at(0.25).unwrap(), Color::RGB(255, 255, 255));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Creates a gradient with the given colors evenly spaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidOperation` if `colors` is empty.
+    pub fn new(colors: Vec<Color>) -> Result<Self, ColorError> {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        let stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| (i as f32 / last, color))
+            .collect();
+
+        Self::with_positions(stops)
+    }
+
+    /// Creates a gradient from stops with explicit positions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidOperation` if `stops` is empty,
+    /// a position is outside of `0.0..=1.0`, or the positions are not sorted.
+    pub fn with_positions(stops: Vec<(f32, Color)>) -> Result<Self, ColorError> {
+        if stops.is_empty() {
+            return Err(ColorError::InvalidOperation(
+                "a gradient needs at least one stop",
+            ));
+        }
+        if stops
+            .iter()
+            .any(|(position, _)| !(0.0..=1.0).contains(position))
+        {
+            return Err(ColorError::InvalidOperation(
+                "gradient stop positions must be within 0.0..=1.0",
+            ));
+        }
+        if stops.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            return Err(ColorError::InvalidOperation(
+                "gradient stop positions must be sorted",
+            ));
+        }
+
+        Ok(Self { stops })
+    }
+
+    /// Returns the stops of the gradient.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Returns the color of the gradient at `t`.
+    ///
+    /// `t` is clamped to `0.0..=1.0`, and positions before the first or after the last stop
+    /// take the color of that stop.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if a surrounding stop can not be resolved to RGB (e.g. `Color::Empty`).
+    pub fn at(&self, t: f32) -> Result<Color, ColorError> {
+        let t = t.clamp(0.0, 1.0);
+
+        let end = self
+            .stops
+            .iter()
+            .position(|(position, _)| *position >= t)
+            .unwrap_or(self.stops.len() - 1);
+        let start = end.saturating_sub(1);
+
+        let (start_position, start_color) = self.stops[start];
+        let (end_position, end_color) = self.stops[end];
+
+        let span = end_position - start_position;
+        let local = if span > 0.0 {
+            ((t - start_position) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        Self::interpolate(start_color, end_color, local)
+    }
+
+    /// Returns `n` evenly spaced colors of the gradient, including both ends.
+    ///
+    /// A single sample takes the color at the start of the gradient.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if a stop can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Gradient};
+    ///
+    /// let gradient = Gradient::new(vec![Color::RGB(0, 0, 0), Color::RGB(200, 0, 0)]).unwrap();
+    ///
+    /// # #[cfg(not(feature = "oklch"))]
+    /// assert_eq!(
+    ///     gradient.sample(3).unwrap(),
+    ///     vec![Color::RGB(0, 0, 0), Color::RGB(100, 0, 0), Color::RGB(200, 0, 0)]
+    /// );
+    /// ```
+    pub fn sample(&self, n: usize) -> Result<Vec<Color>, ColorError> {
+        let last = n.saturating_sub(1).max(1) as f32;
+        (0..n).map(|i| self.at(i as f32 / last)).collect()
+    }
+
+    /// Interpolates between two stops in RGB, or in OKLCH with the `oklch` feature.
+    fn interpolate(start: Color, end: Color, t: f32) -> Result<Color, ColorError> {
+        if cfg!(feature = "oklch") {
+            return start.interpolate_oklch(end, t);
+        }
+
+        let (r1, g1, b1) = start.to_rgb()?;
+        let (r2, g2, b2) = end.to_rgb()?;
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        Ok(Color::RGB(lerp(r1, r2), lerp(g1, g2), lerp(b1, b2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "oklch"))]
+    #[test]
+    fn test_positioned_stops() {
+        let gradient = Gradient::with_positions(vec![
+            (0.0, Color::RGB(0, 0, 0)),
+            (0.25, Color::RGB(200, 100, 0)),
+            (1.0, Color::RGB(0, 100, 200)),
+        ])
+        .unwrap();
+
+        assert_eq!(gradient.at(0.0).unwrap(), Color::RGB(0, 0, 0));
+        assert_eq!(gradient.at(0.125).unwrap(), Color::RGB(100, 50, 0));
+        assert_eq!(gradient.at(0.25).unwrap(), Color::RGB(200, 100, 0));
+        assert_eq!(gradient.at(0.625).unwrap(), Color::RGB(100, 100, 100));
+        assert_eq!(gradient.at(1.0).unwrap(), Color::RGB(0, 100, 200));
+        assert_eq!(gradient.at(2.0).unwrap(), Color::RGB(0, 100, 200));
+    }
+
+    #[cfg(not(feature = "oklch"))]
+    #[test]
+    fn test_evenly_spaced_stops() {
+        let gradient = Gradient::new(vec![Color::RGB(0, 0, 0), Color::RGB(200, 200, 200)]).unwrap();
+        assert_eq!(
+            gradient.stops(),
+            &[(0.0, Color::RGB(0, 0, 0)), (1.0, Color::RGB(200, 200, 200))]
+        );
+        assert_eq!(gradient.at(0.5).unwrap(), Color::RGB(100, 100, 100));
+
+        let single = Gradient::new(vec![Color::Red]).unwrap();
+        assert_eq!(single.at(0.7).unwrap(), Color::RGB(205, 0, 0));
+    }
+
+    #[cfg(not(feature = "oklch"))]
+    #[test]
+    fn test_sample() {
+        let gradient = Gradient::new(vec![
+            Color::RGB(0, 0, 0),
+            Color::RGB(200, 100, 0),
+            Color::RGB(0, 100, 200),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            gradient.sample(5).unwrap(),
+            vec![
+                Color::RGB(0, 0, 0),
+                Color::RGB(100, 50, 0),
+                Color::RGB(200, 100, 0),
+                Color::RGB(100, 100, 100),
+                Color::RGB(0, 100, 200),
+            ]
+        );
+        assert_eq!(gradient.sample(1).unwrap(), vec![Color::RGB(0, 0, 0)]);
+        assert!(gradient.sample(0).unwrap().is_empty());
+
+        let broken = Gradient::new(vec![Color::Red, Color::Empty]).unwrap();
+        assert!(broken.sample(2).is_err());
+    }
+
+    #[cfg(feature = "oklch")]
+    #[test]
+    fn test_oklch_interpolation() {
+        let (red, blue) = (Color::RGB(255, 0, 0), Color::RGB(0, 0, 255));
+        let gradient = Gradient::with_positions(vec![(0.0, red), (0.5, blue), (1.0, red)]).unwrap();
+
+        assert_eq!(gradient.at(0.0).unwrap(), red);
+        assert_eq!(gradient.at(0.5).unwrap(), blue);
+        assert_eq!(
+            gradient.at(0.25).unwrap(),
+            red.interpolate_oklch(blue, 0.5).unwrap()
+        );
+        assert_eq!(
+            gradient.at(0.75).unwrap(),
+            blue.interpolate_oklch(red, 0.5).unwrap()
+        );
+        assert_ne!(gradient.at(0.25).unwrap(), Color::RGB(128, 0, 128));
+        assert_eq!(gradient.sample(3).unwrap(), vec![red, blue, red]);
+    }
+
+    #[test]
+    fn test_invalid_stops() {
+        assert!(Gradient::new(vec![]).is_err());
+        assert!(Gradient::with_positions(vec![(1.5, Color::Red)]).is_err());
+        assert!(Gradient::with_positions(vec![(0.5, Color::Red), (0.2, Color::Blue)]).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! A grid of styled terminal cells, rendered with minimal style transitions.
+
+use crate::{styling_enabled, ColorError, Style};
+
+/// A fixed size grid of terminal cells, each holding a character and its style
+///
+/// Rendering walks the grid row by row and only emits the escape sequences needed
+/// to go from one cell's style to the next (see [`Style::diff`]), which keeps the output small
+/// for large areas sharing the same style.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{CellGrid, Color, Style};
+///
+/// let red = Style::builder().foreground(Color::Red).build();
+///
+/// let mut grid = CellGrid::new(2, 1);
+/// grid.set(0, 0, 'o', red).unwrap();
+/// grid.set(1, 0, 'k', red).unwrap();
+///
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(grid.render(), "\x1b[31mok\x1b[0m");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CellGrid {
+    width: usize,
+    height: usize,
+    cells: Vec<(char, Style)>,
+}
+
+impl CellGrid {
+    /// Creates a grid of the given size filled with unstyled spaces.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![(' ', Style::default()); width * height],
+        }
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the character and style of the cell at column `x` and row `y`,
+    /// or `None` if the position is outside of the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<(char, Style)> {
+        self.index(x, y).map(|index| self.cells[index])
+    }
+
+    /// Sets the character and style of the cell at column `x` and row `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidOperation` if the position is outside of the grid.
+    pub fn set(&mut self, x: usize, y: usize, ch: char, style: Style) -> Result<(), ColorError> {
+        let index = self.index(x, y).ok_or(ColorError::InvalidOperation(
+            "the cell position is outside of the grid",
+        ))?;
+        self.cells[index] = (ch, style);
+        Ok(())
+    }
+
+    /// Renders the grid row by row, separated by newlines.
+    ///
+    /// A reset is appended only if the last cell leaves a style active.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut current = Style::default();
+
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                out.push('\n');
+            }
+            for (ch, style) in row {
+                out.push_str(&current.diff(style));
+                out.push(*ch);
+                current = *style;
+            }
+        }
+
+        if !cfg!(feature = "no-color") && styling_enabled() && !current.is_empty() {
+            out.push_str(Style::reset_sequence());
+        }
+
+        out
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_render_minimal_transitions() {
+        use crate::Color;
+
+        let red = Style::builder().foreground(Color::Red).build();
+        let blue = Style::builder().foreground(Color::Blue).build();
+
+        let mut grid = CellGrid::new(2, 2);
+        grid.set(0, 0, 'a', red).unwrap();
+        grid.set(1, 0, 'b', red).unwrap();
+        grid.set(0, 1, 'c', blue).unwrap();
+        grid.set(1, 1, 'd', blue).unwrap();
+
+        assert_eq!(grid.render(), "\x1b[31mab\n\x1b[34mcd\x1b[0m");
+
+        grid.set(1, 1, 'd', Style::default()).unwrap();
+        assert_eq!(grid.render(), "\x1b[31mab\n\x1b[34mc\x1b[39md");
+    }
+
+    #[test]
+    fn test_cell_access() {
+        let mut grid = CellGrid::new(3, 2);
+        assert_eq!(grid.get(2, 1).map(|(ch, _)| ch), Some(' '));
+        assert!(grid.set(3, 0, 'x', Style::default()).is_err());
+        assert!(grid.set(0, 2, 'x', Style::default()).is_err());
+        assert!(grid.get(0, 2).is_none());
+
+        grid.set(2, 1, 'x', Style::default()).unwrap();
+        assert_eq!(grid.get(2, 1).map(|(ch, _)| ch), Some('x'));
+        assert_eq!(grid.render(), "   \n  x");
+    }
+}
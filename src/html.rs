@@ -0,0 +1,124 @@
+//! Rendering of styled text as HTML, for showing terminal output in a browser.
+
+use crate::{ColoredString, Style, UnderlineStyle};
+
+impl ColoredString {
+    /// Renders the colored string as an HTML `<span>` with an inline CSS style.
+    ///
+    /// The colors use their RGB value (the predefined colors through their canonical xterm values),
+    /// and the text is escaped. A string without any style is rendered as the escaped text only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, Stylish};
+    ///
+    /// let text = "a < b".styled(Style::builder().foreground(Color::Red).bold().build());
+    /// assert_eq!(
+    ///     text.to_html(),
+    ///     r#"<span style="color:#CD0000;font-weight:bold">a &lt; b</span>"#
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        let text = escape_html(&self.string);
+        let css = css_declarations(&self.style);
+        if css.is_empty() {
+            return text;
+        }
+
+        format!("<span style=\"{}\">{}</span>", css.join(";"), text)
+    }
+}
+
+/// Returns the CSS declarations equivalent to the style.
+fn css_declarations(style: &Style) -> Vec<String> {
+    let mut css = Vec::new();
+
+    if let Some(hex) = style.foreground.to_hex_string() {
+        css.push(format!("color:{}", hex));
+    }
+    if let Some(hex) = style.background.to_hex_string() {
+        css.push(format!("background-color:{}", hex));
+    }
+    if style.bold {
+        css.push("font-weight:bold".to_string());
+    }
+    if style.dim {
+        css.push("opacity:0.5".to_string());
+    }
+    if style.italic {
+        css.push("font-style:italic".to_string());
+    }
+    if style.underline {
+        css.push("text-decoration:underline".to_string());
+        let line_style = match style.underline_style {
+            UnderlineStyle::Straight => None,
+            UnderlineStyle::Double => Some("double"),
+            UnderlineStyle::Curly => Some("wavy"),
+            UnderlineStyle::Dotted => Some("dotted"),
+            UnderlineStyle::Dashed => Some("dashed"),
+        };
+        if let Some(line_style) = line_style {
+            css.push(format!("text-decoration-style:{}", line_style));
+        }
+        if let Some(hex) = style.underline_color.to_hex_string() {
+            css.push(format!("text-decoration-color:{}", hex));
+        }
+    }
+    if style.hidden {
+        css.push("visibility:hidden".to_string());
+    }
+
+    css
+}
+
+/// Escapes the characters with a special meaning in HTML text and attributes.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Stylish};
+
+    #[test]
+    fn test_to_html() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 128, 0))
+            .background(Color::Blue)
+            .italic()
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .build();
+
+        assert_eq!(
+            "<b> & 'c'".styled(style).to_html(),
+            "<span style=\"color:#FF8000;background-color:#0000EE;font-style:italic;\
+             text-decoration:underline;text-decoration-style:wavy\">\
+             &lt;b&gt; &amp; &#39;c&#39;</span>"
+        );
+    }
+
+    #[test]
+    fn test_unstyled_html() {
+        assert_eq!(ColoredString::plain("x > y").to_html(), "x &gt; y");
+        assert_eq!(
+            "invalid"
+                .styled(Style::builder().foreground(Color::HEX("#zz")).build())
+                .to_html(),
+            "invalid"
+        );
+    }
+}
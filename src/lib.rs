@@ -41,18 +41,145 @@
 //!
 //! Please make sure your terminal supports ANSI colors by using the [`is_color_available`] function before attempting to print colored text.
 
+use std::borrow::Cow;
 use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "termcolor")]
+mod color_spec;
+mod env;
+mod gradient;
+mod grid;
+#[cfg(feature = "html")]
+mod html;
+mod line;
+pub mod palettes;
+mod parse;
+#[cfg(feature = "serde")]
+mod serialize;
+mod theme;
+mod width;
+mod writer;
+
+pub use env::{
+    check_color_support, check_color_support_for, check_color_support_with, color_enabled,
+    color_enabled_for, detect_terminal_background, select_theme, terminal_size, terminal_width,
+    Background, ColorPrecedence, Stream,
+};
+pub use gradient::Gradient;
+pub use grid::CellGrid;
+pub use line::{render_all, StyledLine};
+pub use parse::{parse_ansi, strip_ansi};
+pub use theme::Theme;
+pub use writer::StyledWriter;
+
+/// The most used items, for a single glob import.
+///
+/// # Example
+///
+/// ```
+/// use inksac::prelude::*;
+///
+/// let warning = colorize("warning", Color::Yellow);
+/// let title = "title".styled(Style::builder().bold().build());
+/// println!("{} {}", title, warning);
+/// ```
+pub mod prelude {
+    pub use crate::{
+        colorize, colorize_bg, Color, ColoredString, Style, StyleBuilder, Stylish, StylishIter,
+    };
+}
 
 pub mod ansi_base {
     pub const RESET: &str = "\x1b[0m";
+    // The short form of `RESET`, for terminals that do not understand the explicit `0`
+    pub const RESET_SHORT: &str = "\x1b[m";
     pub const BOLD: &str = "\x1b[1m";
     pub const DIM: &str = "\x1b[2m";
     pub const ITALIC: &str = "\x1b[3m";
     pub const UNDERLINE: &str = "\x1b[4m";
+    pub const HIDDEN: &str = "\x1b[8m";
+
+    // Resets a single attribute rather than everything like `RESET`
+    pub const RESET_BOLD_DIM: &str = "\x1b[22m";
+    pub const RESET_ITALIC: &str = "\x1b[23m";
+    pub const RESET_UNDERLINE: &str = "\x1b[24m";
+    pub const RESET_REVERSE: &str = "\x1b[27m";
+    pub const RESET_HIDDEN: &str = "\x1b[28m";
+
+    // Defines a reset sequence along with its bare parameter, for building combined sequences
+    macro_rules! reset_with_param {
+        ($sequence:ident, $param:ident, $code:literal) => {
+            pub const $sequence: &str = concat!("\x1b[", $code, "m");
+            pub(crate) const $param: &str = $code;
+        };
+    }
+
+    reset_with_param!(RESET_FOREGROUND, RESET_FOREGROUND_PARAM, "39");
+    reset_with_param!(RESET_BACKGROUND, RESET_BACKGROUND_PARAM, "49");
+
+    /// Returns the SGR sequence with the given parameters, e.g. `\x1b[1;31m` for `&[1, 31]`.
+    ///
+    /// This is meant for codes without a dedicated helper, such as strikethrough (`9`) or overline (`53`).
+    /// No parameters give an empty string rather than `\x1b[m`, which terminals read as a reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{ansi_base, Color, ColorSupport};
+    ///
+    /// assert_eq!(ansi_base::sgr(&[9]), "\x1b[9m");
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(
+    ///     ansi_base::sgr(&[38, 2, 255, 128, 0]),
+    ///     Color::RGB(255, 128, 0).to_fg_for(ColorSupport::TrueColor)
+    /// );
+    /// ```
+    pub fn sgr(params: &[u16]) -> String {
+        let params: Vec<String> = params.iter().map(u16::to_string).collect();
+        sgr_str(&params)
+    }
+
+    /// Same as [`sgr`] for parameters that are already formatted,
+    /// which also allows sub-parameters such as the `4:3` of a curly underline.
+    pub(crate) fn sgr_str(params: &[String]) -> String {
+        if params.is_empty() {
+            return String::new();
+        }
+
+        format!("\x1b[{}m", params.join(";"))
+    }
+
+    /// Returns the OSC 0 sequence setting the window title of the terminal.
+    ///
+    /// Control characters are stripped from the title, so it cannot end the sequence early
+    /// and smuggle in other escape sequences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::ansi_base::set_title;
+    ///
+    /// assert_eq!(set_title("build: ok"), "\x1b]0;build: ok\x07");
+    /// assert_eq!(set_title("evil\x07\x1b[2J"), "\x1b]0;evil[2J\x07");
+    /// ```
+    pub fn set_title(title: &str) -> String {
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        format!("\x1b]0;{}\x07", title)
+    }
+
+    /// Writes the sequence from [`set_title`] to the writer, e.g. `std::io::stdout()`.
+    pub fn write_title(writer: &mut impl std::io::Write, title: &str) -> std::io::Result<()> {
+        writer.write_all(set_title(title).as_bytes())?;
+        writer.flush()
+    }
 }
 
-// FIX!: ASAP: what the actual fucking fuck just return boolean
 /// Check if the terminal supports ANSI colors
+///
+/// See [`color_enabled`] for a plain boolean check.
 pub fn is_color_available() -> Result<(), &'static str> {
     if std::env::var("TERM").is_ok() {
         return Ok(());
@@ -60,418 +187,5766 @@ pub fn is_color_available() -> Result<(), &'static str> {
     Err("Terminal does not support ANSI colors")
 }
 
-/// String with the colored text
+static STYLING_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables styling globally
+///
+/// While disabled, every [`ColoredString`] is written as plain text, whatever the environment
+/// or the color support of the terminal is. This is meant for a `--no-color` command line flag.
+/// A [`Style`] itself and its [`Style::diff`] write nothing either, so [`StyledWriter`] and [`CellGrid`]
+/// output plain text too.
 ///
 /// # Example
 ///
 /// ```
-/// use inksac::{Color, Style, Stylish};
+/// use inksac::{set_styling_enabled, Color, Stylish, Style};
 ///
-/// let TITLESTYLE: Style = Style{
-///     foreground: Color::Green,
-///     background: Color::Red,
-///     ..Default::default()
-/// };
-/// let title_text = "Hello World".styled(TITLESTYLE);
-/// println!("{}", title_text);
+/// let text = "plain".styled(Style::builder().foreground(Color::Red).build());
+///
+/// set_styling_enabled(false);
+/// assert_eq!(text.to_string(), "plain");
+/// set_styling_enabled(true);
 /// ```
-#[derive(Debug, Clone)]
-pub struct ColoredString {
-    pub string: String,
-    pub style: Style,
-}
-
-impl ColoredString {
-    /// Creates a new `ColoredString` with the given string and style.
-    pub fn new(string: &str, style: Style) -> Self {
-        Self {
-            string: string.into(),
-            style,
-        }
-    }
-
-    /// Returns the non colored String
-    pub fn to_no_style(&self) -> String {
-        self.string.clone()
-    }
+pub fn set_styling_enabled(enabled: bool) {
+    STYLING_ENABLED.store(enabled, Ordering::Relaxed);
 }
 
-impl fmt::Display for ColoredString {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}{}", self.style, self.string, ansi_base::RESET)
-    }
+/// Returns `false` if styling was disabled with [`set_styling_enabled`]
+pub fn styling_enabled() -> bool {
+    STYLING_ENABLED.load(Ordering::Relaxed)
 }
 
-// FIX!: LATER: trait name should be verb
-/// Trait for types that can be styled with a `Style`
-pub trait Stylish {
-    // FIX!: LATER: trait's only method should have consistent name with the trait
-    fn styled(self, style: Style) -> ColoredString;
+/// The escape sequence written after styled text to reset all styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResetSequence {
+    /// `\x1b[0m`, understood by every terminal supporting colors
+    #[default]
+    Full,
+    /// `\x1b[m`, for legacy terminals that choke on the explicit `0`
+    Short,
 }
 
-// FIX: blanket impl for everything that implements `ToString` or `AsRef<str>`
-impl Stylish for String {
-    fn styled(self, style: Style) -> ColoredString {
-        ColoredString::new(&self, style)
+impl ResetSequence {
+    /// Returns the escape sequence.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResetSequence::Full => ansi_base::RESET,
+            ResetSequence::Short => ansi_base::RESET_SHORT,
+        }
     }
 }
 
-impl<'a> Stylish for &'a str {
-    fn styled(self, style: Style) -> ColoredString {
-        ColoredString::new(self, style)
-    }
-}
+static SHORT_RESET: AtomicBool = AtomicBool::new(false);
 
-/// A struct representing various styles that can be applied to a string.
+/// Sets the reset sequence written after styled text globally
 ///
-/// Styles include foreground and background color, boldness, dimness, italicization, and underlining.
+/// Every [`ColoredString`], [`StyledWriter`] and [`CellGrid`] uses it, see [`Style::reset_sequence`].
 ///
 /// # Example
 ///
 /// ```
-/// use inksac::{Color, Style};
+/// use inksac::{set_reset_sequence, Color, ResetSequence, Stylish, Style};
 ///
-/// let TITLESTYLE: Style = Style{
-///     foreground: Color::Green,
-///     background: Color::Red,
-///     ..Default::default()
-/// };
+/// let text = "legacy".styled(Style::builder().foreground(Color::Red).build());
+///
+/// set_reset_sequence(ResetSequence::Short);
+/// # #[cfg(not(feature = "no-color"))]
+/// assert!(text.to_string().ends_with("\x1b[m"));
+/// set_reset_sequence(ResetSequence::Full);
 /// ```
-#[derive(Debug, Clone, Copy, Default)]
-pub struct Style {
-    pub foreground: Color,
-    pub background: Color,
-    pub bold: bool,
-    pub dim: bool,
-    pub italic: bool,
-    pub underline: bool,
+pub fn set_reset_sequence(sequence: ResetSequence) {
+    SHORT_RESET.store(sequence == ResetSequence::Short, Ordering::Relaxed);
 }
 
-impl fmt::Display for Style {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let fg = if self.foreground != Color::Empty {
-            self.foreground.to_fg()
-        } else {
-            Color::Empty.to_fg()
-        };
-        let bg = if self.background != Color::Empty {
-            self.background.to_bg()
-        } else {
-            Color::Empty.to_bg()
-        };
-        let bold = if self.bold { ansi_base::BOLD } else { "" };
-        let dim = if self.dim { ansi_base::DIM } else { "" };
-        let italic = if self.italic { ansi_base::ITALIC } else { "" };
-        let underline = if self.underline {
-            ansi_base::UNDERLINE
-        } else {
-            ""
-        };
+/// Errors that can occur while working with colors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// The hexadecimal color code could not be parsed.
+    InvalidHexCode(String),
 
-        write!(f, "{}{}{}{}{}{}", fg, bg, bold, dim, italic, underline)
-    }
-}
+    /// The operation can not be performed with the given colors.
+    InvalidOperation(&'static str),
 
-impl Style {
-    /// Creates a new instance of `StyleBuilder` with default values.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use inksac::Style;
-    ///
-    /// let builder = Style::builder();
-    /// ```
-    pub fn builder() -> StyleBuilder {
-        StyleBuilder::default()
-    }
-}
+    /// The text could not be parsed as a color.
+    ParseError(String),
 
-// FIX!: unnecessary builder pattern
-/// A builder struct for constructing a `Style` instance with various configurations.
-pub struct StyleBuilder {
-    style: Style,
+    /// A color component is outside of its valid range.
+    InvalidColorValue(String),
+
+    /// A theme could not be read, with the location of the mistake.
+    InvalidTheme(String),
+
+    /// The terminal does not support colors at all.
+    NoTerminalSupport,
+
+    /// The terminal does not support the requested color mode.
+    UnsupportedColorMode {
+        requested: ColorSupport,
+        available: ColorSupport,
+        /// Why the mode was needed, may be empty.
+        reason: String,
+    },
 }
 
-impl Default for StyleBuilder {
-    /// Constructs a new `StyleBuilder` with a default `Style`.
-    fn default() -> Self {
-        Self {
-            style: Style::default(),
-        }
-    }
+/// The broad category of a [`ColorError`], see [`ColorError::category`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The input was invalid, which usually points to a bug in the caller.
+    InvalidInput,
+
+    /// The environment (e.g. the terminal) can not display the color.
+    Environment,
 }
 
-impl StyleBuilder {
-    /// Sets the foreground color of the style.
-    ///
-    /// # Arguments
-    ///
-    /// * `color` - An option containing a `Color` enum variant to set as the foreground color.
+impl ColorError {
+    /// Creates the error for a color component outside of its valid range.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::{StyleBuilder, Color};
+    /// use inksac::ColorError;
     ///
-    /// let style = StyleBuilder::default()
-    ///     .foreground(Color::Green)
-    ///     .build();
+    /// let err = ColorError::out_of_range("hue", 400.0, "0..=360");
+    /// assert_eq!(err, ColorError::InvalidColorValue("hue 400 is outside of 0..=360".to_string()));
     /// ```
-    pub fn foreground(mut self, color: Color) -> Self {
-        // FIX!: ASAP: take & return mutable reference rather than taking ownership
-        // | e.g. (&mut self, color: Color) -> &mut Self
-        // | also applys to every builder pattern methods below
-        self.style.foreground = color;
-        self
+    pub fn out_of_range(component: &str, value: impl fmt::Display, range: &str) -> Self {
+        ColorError::InvalidColorValue(format!("{} {} is outside of {}", component, value, range))
     }
 
-    /// Sets the background color of the style.
-    ///
-    /// # Arguments
+    /// Creates the error for a color that can not be converted from one representation to another.
     ///
-    /// * `color` - An option containing a `Color` enum variant to set as the background color.
+    /// There is no dedicated variant for conversions, so this gives `ColorError::InvalidColorValue`.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::{StyleBuilder, Color};
+    /// use inksac::ColorError;
     ///
-    /// let style = StyleBuilder::default()
-    ///     .background(Color::Red)
-    ///     .build();
+    /// let err = ColorError::conversion("HEX", "RGB", "the code has 5 digits");
+    /// assert_eq!(err.to_string(), "Invalid color value: can not convert HEX to RGB, the code has 5 digits");
     /// ```
-    pub fn background(mut self, color: Color) -> Self {
-        self.style.background = color;
-        self
+    pub fn conversion(from: &str, to: &str, reason: impl fmt::Display) -> Self {
+        ColorError::InvalidColorValue(format!("can not convert {} to {}, {}", from, to, reason))
     }
 
-    /// Sets the bold attribute of the style to true.
+    /// Creates the error for a terminal that can not display the requested color mode.
+    ///
+    /// This gives `ColorError::UnsupportedColorMode` carrying the reason, also for a terminal
+    /// without any color support (`ColorSupport::NoColor`), so the requested mode is kept.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::StyleBuilder;
+    /// use inksac::{ColorError, ColorSupport};
     ///
-    /// let style = StyleBuilder::default()
-    ///     .bold()
-    ///     .build();
+    /// let err = ColorError::terminal(ColorSupport::TrueColor, ColorSupport::NoColor, "the logo is RGB");
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Unsupported color mode: TrueColor was requested but only NoColor is available, the logo is RGB"
+    /// );
     /// ```
-    pub fn bold(mut self) -> Self {
-        self.style.bold = true;
-        self
+    pub fn terminal(
+        requested: ColorSupport,
+        available: ColorSupport,
+        reason: impl fmt::Display,
+    ) -> Self {
+        ColorError::UnsupportedColorMode {
+            requested,
+            available,
+            reason: reason.to_string(),
+        }
     }
 
-    /// Sets the dim attribute of the style to true.
+    /// Returns the category of the error.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::StyleBuilder;
+    /// use inksac::{ColorError, ErrorCategory};
     ///
-    /// let style = StyleBuilder::default()
-    ///     .dim()
-    ///     .build();
+    /// assert_eq!(ColorError::NoTerminalSupport.category(), ErrorCategory::Environment);
+    /// assert_eq!(
+    ///     ColorError::InvalidHexCode("#xyz".to_string()).category(),
+    ///     ErrorCategory::InvalidInput
+    /// );
     /// ```
-    pub fn dim(mut self) -> Self {
-        self.style.dim = true;
-        self
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ColorError::InvalidHexCode(_)
+            | ColorError::InvalidOperation(_)
+            | ColorError::ParseError(_)
+            | ColorError::InvalidColorValue(_)
+            | ColorError::InvalidTheme(_) => ErrorCategory::InvalidInput,
+            ColorError::NoTerminalSupport | ColorError::UnsupportedColorMode { .. } => {
+                ErrorCategory::Environment
+            }
+        }
     }
 
-    /// Sets the italic attribute of the style to true.
+    /// Returns `true` if the error comes from the environment rather than the input.
+    ///
+    /// Such errors can usually be handled by falling back to plain or less colorful output,
+    /// while the others point to a mistake that should be surfaced.
+    pub fn is_recoverable(&self) -> bool {
+        self.category() == ErrorCategory::Environment
+    }
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::InvalidHexCode(code) => write!(f, "Invalid hex code: {}", code),
+            ColorError::InvalidOperation(reason) => write!(f, "Invalid operation: {}", reason),
+            ColorError::ParseError(input) => write!(f, "Invalid color: {}", input),
+            ColorError::InvalidColorValue(reason) => write!(f, "Invalid color value: {}", reason),
+            ColorError::InvalidTheme(reason) => write!(f, "Invalid theme: {}", reason),
+            ColorError::NoTerminalSupport => write!(f, "Terminal does not support colors"),
+            ColorError::UnsupportedColorMode {
+                requested,
+                available,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "Unsupported color mode: {:?} was requested but only {:?} is available",
+                    requested, available
+                )?;
+                if !reason.is_empty() {
+                    write!(f, ", {}", reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+/// The level of color support of a terminal
+///
+/// The levels are ordered from the least to the most capable,
+/// so `support >= ColorSupport::Color256` checks for at least 256 colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    /// No color support, only plain text is emitted.
+    NoColor,
+
+    /// The 16 basic ANSI colors.
+    Basic,
+
+    /// The 256 color palette.
+    Color256,
+
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Returns `true` for `ColorSupport::TrueColor`.
+    ///
+    /// These checks match a single level, compare the levels to check for at least one
+    /// (e.g. `support >= ColorSupport::Color256`).
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::StyleBuilder;
+    /// use inksac::ColorSupport;
     ///
-    /// let style = StyleBuilder::default()
-    ///     .italic()
-    ///     .build();
+    /// assert!(ColorSupport::TrueColor.is_truecolor());
+    /// assert!(!ColorSupport::TrueColor.is_256());
+    /// assert!(ColorSupport::TrueColor >= ColorSupport::Color256);
     /// ```
-    pub fn italic(mut self) -> Self {
-        self.style.italic = true;
-        self
+    pub fn is_truecolor(self) -> bool {
+        self == ColorSupport::TrueColor
     }
 
-    /// Sets the underline attribute of the style to true.
+    /// Returns `true` for `ColorSupport::Color256`.
+    pub fn is_256(self) -> bool {
+        self == ColorSupport::Color256
+    }
+
+    /// Returns `true` for `ColorSupport::Basic`.
+    pub fn is_basic(self) -> bool {
+        self == ColorSupport::Basic
+    }
+
+    /// Returns `true` for every level but `ColorSupport::NoColor`.
+    pub fn has_color(self) -> bool {
+        self != ColorSupport::NoColor
+    }
+
+    /// Returns the number of colors the support level can display.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::StyleBuilder;
+    /// use inksac::ColorSupport;
     ///
-    /// let style = StyleBuilder::default()
-    ///     .underline()
-    ///     .build();
+    /// assert_eq!(ColorSupport::Color256.max_colors(), 256);
+    /// assert_eq!(ColorSupport::TrueColor.max_colors(), 16_777_216);
     /// ```
-    pub fn underline(mut self) -> Self {
-        self.style.underline = true;
-        self
+    pub fn max_colors(self) -> u32 {
+        match self {
+            ColorSupport::NoColor => 0,
+            ColorSupport::Basic => 16,
+            ColorSupport::Color256 => 256,
+            ColorSupport::TrueColor => 1 << 24,
+        }
     }
 
-    /// Builds and returns a `Style` instance with the configurations set in the builder.
+    /// Returns the lowest support level that can display `n` colors.
+    ///
+    /// This is the inverse of [`ColorSupport::max_colors`], e.g. for the color count reported by `tput colors`.
     ///
     /// # Example
     ///
     /// ```
-    /// use inksac::{StyleBuilder,Color};
+    /// use inksac::ColorSupport;
     ///
-    /// let style = StyleBuilder::default()
-    ///     .foreground(Color::Green)
-    ///     .bold()
-    ///     .build();
+    /// assert_eq!(ColorSupport::from_color_count(8), ColorSupport::Basic);
+    /// assert_eq!(ColorSupport::from_color_count(88), ColorSupport::Color256);
     /// ```
-    pub fn build(self) -> Style {
-        self.style
+    pub fn from_color_count(n: u32) -> ColorSupport {
+        [
+            ColorSupport::NoColor,
+            ColorSupport::Basic,
+            ColorSupport::Color256,
+        ]
+        .into_iter()
+        .find(|support| n <= support.max_colors())
+        .unwrap_or(ColorSupport::TrueColor)
     }
 }
 
-/// Represents the different colors that can be used for text foreground and background styling.
-///
-/// The enum provides several options to specify colors:
-/// - Predefined color values (e.g., `Black`, `Red`, `Green`, etc.)
-/// - RGB values with the `RGB` variant
-/// - Hexadecimal color codes with the `HEX` variant
-///
-/// # Examples
+/// String with the colored text
 ///
-/// Using predefined color values:
+/// # Example
 ///
 /// ```
-/// use inksac::Color;
+/// use inksac::{Color, Style, Stylish};
 ///
-/// let red = Color::Red;
-/// let green = Color::Green;
-/// ```
-///
-/// Using RGB values:
-///
-/// ```
-/// use inksac::Color;
-///
-/// let custom_color = Color::RGB(128, 0, 128);
-/// ```
-///
-/// Using a hexadecimal color code:
-///
-/// ```
-/// use inksac::Color;
-///
-/// let custom_color = Color::HEX("#800080");
+/// let TITLESTYLE: Style = Style{
+///     foreground: Color::Green,
+///     background: Color::Red,
+///     ..Default::default()
+/// };
+/// let title_text = "Hello World".styled(TITLESTYLE);
+/// println!("{}", title_text);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum Color {
-    Black,
-    Red,
-    Green,
-    Yellow,
-    Blue,
-    Magenta,
-    Cyan,
-    White,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColoredString {
+    pub string: Cow<'static, str>,
+    pub style: Style,
+}
 
-    /// Represents an absence of color.
-    #[default]
-    Empty,
+impl ColoredString {
+    /// Creates a new `ColoredString` with the given string and style.
+    ///
+    /// The string is copied, use [`ColoredString::from_static`] or [`ColoredString::from_cow`]
+    /// to avoid the allocation.
+    pub fn new(string: &str, style: Style) -> Self {
+        Self::from_cow(Cow::Owned(string.to_owned()), style)
+    }
 
-    /// Specifies a color using RGB values.
-    RGB(u8, u8, u8),
+    /// Creates a `ColoredString` borrowing a `'static` string, without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// const TITLE: &str = "inksac";
+    /// let title = ColoredString::from_static(TITLE, Style::builder().bold().build());
+    /// assert_eq!(title.as_str().as_ptr(), TITLE.as_ptr());
+    /// ```
+    pub fn from_static(string: &'static str, style: Style) -> Self {
+        Self::from_cow(Cow::Borrowed(string), style)
+    }
 
-    /// Specifies a color using a hexadecimal color code.
-    HEX(&'static str),
-}
+    /// Creates a `ColoredString` from a borrowed `'static` or an owned string, without copying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use inksac::{ColoredString, Style};
+    ///
+    /// let owned = ColoredString::from_cow(Cow::Owned(format!("{} files", 3)), Style::default());
+    /// assert_eq!(owned.as_str(), "3 files");
+    /// ```
+    pub fn from_cow(string: Cow<'static, str>, style: Style) -> Self {
+        Self { string, style }
+    }
 
-impl Color {
-    /// Converts the `Color` enum variant to its corresponding foreground ANSI escape code string.
-    fn to_fg(self) -> String {
-        match self {
-            Color::Black => "\x1b[30m".to_string(),
-            Color::Red => "\x1b[31m".to_string(),
-            Color::Green => "\x1b[32m".to_string(),
-            Color::Yellow => "\x1b[33m".to_string(),
-            Color::Blue => "\x1b[34m".to_string(),
-            Color::Magenta => "\x1b[35m".to_string(),
-            Color::Cyan => "\x1b[36m".to_string(),
-            Color::White => "\x1b[37m".to_string(),
-            Color::Empty => "".to_string(),
-            Color::RGB(r, g, b) => format!("\x1b[38;2;{};{};{}m", r, g, b),
-            Color::HEX(code) => {
-                // FIX: converting str to integer and back to String
-                let (r, g, b) = match Self::hex_to_rgb(code) {
-                    Some(rgb) => rgb,
-                    None => panic!("Invalid hex code: {}", code),
-                };
+    /// Creates a `ColoredString` without any style.
+    ///
+    /// Useful where styled and unstyled text are mixed, e.g. the cells of a table.
+    /// An empty style writes just the text, without any escape sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::ColoredString;
+    ///
+    /// let cell = ColoredString::plain("n/a");
+    /// assert_eq!(cell.to_string(), "n/a");
+    /// ```
+    pub fn plain(string: &str) -> Self {
+        Self::new(string, Style::default())
+    }
 
-                format!("\x1b[38;2;{};{};{}m", r, g, b)
-            }
+    /// Creates a swatch of `width` block characters (`█`) in the given color, e.g. to preview a theme.
+    ///
+    /// The color is downgraded to the color support of the terminal, so a swatch shows
+    /// the color that would actually be displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString};
+    ///
+    /// let swatch = ColoredString::swatch(Color::Red, 4);
+    /// assert_eq!(&*swatch, "████");
+    /// ```
+    pub fn swatch(color: Color, width: usize) -> Self {
+        let style = Style::builder()
+            .foreground(color.downgrade(check_color_support()))
+            .build();
+        Self::new(&"█".repeat(width), style)
+    }
+
+    /// Creates a swatch of `width` spaces with the given color as background.
+    ///
+    /// Like [`ColoredString::swatch`], the color is downgraded to the color support of the terminal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString};
+    ///
+    /// let swatch = ColoredString::swatch_bg(Color::Blue, 3);
+    /// assert_eq!(&*swatch, "   ");
+    /// ```
+    pub fn swatch_bg(color: Color, width: usize) -> Self {
+        let style = Style::builder()
+            .background(color.downgrade(check_color_support()))
+            .build();
+        Self::new(&" ".repeat(width), style)
+    }
+
+    /// Returns the non colored String
+    pub fn to_no_style(&self) -> String {
+        self.string.to_string()
+    }
+
+    /// Returns the underlying text without the style, without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, Stylish};
+    ///
+    /// let text = "error".styled(Style::builder().foreground(Color::Red).build());
+    /// assert_eq!(text.as_str(), "error");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    /// Returns the style of the colored string.
+    pub fn current_style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns a mutable reference to the style, to change it in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, Stylish};
+    ///
+    /// let mut text = "note".styled(Style::builder().foreground(Color::Blue).build());
+    /// text.style_mut().bold = true;
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text.to_string(), "\x1b[34;1mnote\x1b[0m");
+    /// ```
+    pub fn style_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    /// Returns the length of the underlying text in bytes.
+    ///
+    /// This is neither the number of characters nor the width on the terminal,
+    /// use [`ColoredString::display_width`] for aligning output.
+    pub fn len(&self) -> usize {
+        self.string.len()
+    }
+
+    /// Returns `true` if the underlying text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    /// Returns the number of bytes the colored string takes when displayed.
+    ///
+    /// This includes the escape sequences and the reset, and matches `to_string().len()`
+    /// without building the string, e.g. to size a buffer up front.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, Stylish};
+    ///
+    /// let text = "ok".styled(Style::builder().foreground(Color::Green).build());
+    /// assert_eq!(text.rendered_len(), text.to_string().len());
+    /// ```
+    pub fn rendered_len(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        // the counter never fails
+        let _ = fmt::Write::write_fmt(&mut counter, format_args!("{}", self));
+        counter.0
+    }
+
+    /// Returns the number of terminal columns the underlying text takes.
+    ///
+    /// Wide characters (e.g. CJK) take two columns, and combining or zero width characters take none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("你好", Style::default());
+    /// assert_eq!(text.len(), 6);
+    /// assert_eq!(text.display_width(), 4);
+    /// ```
+    pub fn display_width(&self) -> usize {
+        width::str_width(&self.string)
+    }
+
+    /// Returns the number of user-perceived characters (grapheme clusters) of the underlying text.
+    ///
+    /// The text has three different lengths: [`ColoredString::len`] counts its bytes,
+    /// [`ColoredString::display_width`] the terminal columns it takes, and this method the
+    /// characters a reader sees, e.g. an emoji with a skin tone modifier counts as one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("👍🏽", Style::default());
+    /// assert_eq!(text.len(), 8);
+    /// assert_eq!(text.display_width(), 2);
+    /// assert_eq!(text.grapheme_count(), 1);
+    /// ```
+    pub fn grapheme_count(&self) -> usize {
+        width::grapheme_count(&self.string)
+    }
+
+    /// Returns the number of terminal columns the underlying text takes, ignoring the escape sequences in it.
+    ///
+    /// Unlike [`ColoredString::display_width`], this handles text that already contains escape
+    /// sequences, e.g. output of another program, with [`strip_ansi`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("\x1b[31mred\x1b[0m", Style::default());
+    /// assert_eq!(text.visible_len(), 3);
+    /// ```
+    pub fn visible_len(&self) -> usize {
+        if !self.string.contains('\x1b') {
+            return self.display_width();
         }
+
+        width::str_width(&strip_ansi(&self.string))
     }
 
-    /// Converts the `Color` enum variant to its corresponding background ANSI escape code string.
-    fn to_bg(self) -> String {
-        match self {
-            // FIX!: use `Cow<'static, str>` to avoid `to_string()`
-            Color::Black => "\x1b[40m".to_string(),
-            Color::Red => "\x1b[41m".to_string(),
-            Color::Green => "\x1b[42m".to_string(),
-            Color::Yellow => "\x1b[43m".to_string(),
-            Color::Blue => "\x1b[44m".to_string(),
-            Color::Magenta => "\x1b[45m".to_string(),
-            Color::Cyan => "\x1b[46m".to_string(),
-            Color::White => "\x1b[47m".to_string(),
-            Color::Empty => "".to_string(),
-            Color::RGB(r, g, b) => format!("\x1b[48;2;{};{};{}m", r, g, b),
-            Color::HEX(code) => {
-                let (r, g, b) = match Self::hex_to_rgb(code) {
-                    Some(rgb) => rgb,
-                    None => panic!("Invalid hex code: {}", code),
-                };
+    /// Renders the colored string for a terminal with the given color support.
+    ///
+    /// The colors of the style are downgraded to the given support level,
+    /// and `ColorSupport::NoColor` renders the plain text without any escape sequences.
+    /// The environment is not read, so the output only depends on `support` (and the global toggle,
+    /// see [`set_styling_enabled`]), which keeps tests deterministic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, ColorSupport, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::RGB(250, 10, 10)).build();
+    /// let text = ColoredString::new("Hello", style);
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text.render_with(ColorSupport::Basic), "\x1b[31mHello\x1b[0m");
+    /// assert_eq!(text.render_with(ColorSupport::NoColor), "Hello");
+    /// ```
+    pub fn render_with(&self, support: ColorSupport) -> String {
+        let style = self.style.downgrade(support);
+        if support == ColorSupport::NoColor
+            || cfg!(feature = "no-color")
+            || !styling_enabled()
+            || style.is_empty()
+        {
+            return self.string.to_string();
+        }
 
-                format!("\x1b[48;2;{};{};{}m", r, g, b)
-            }
+        format!("{}{}{}", style, self.string, Style::reset_sequence())
+    }
+
+    /// Lays the style over the given byte range of the text, see [`Style::overlay`].
+    ///
+    /// Returns the line of the text before, inside and after the range,
+    /// leaving out the pieces that are empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidOperation` if the range is outside of the text,
+    /// or does not start and end on character boundaries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("error: not found", Style::builder().foreground(Color::Red).build());
+    /// let line = text.restyle_range(0..5, Style::builder().bold().build()).unwrap();
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(line.to_string(), "\x1b[31;1merror\x1b[0m\x1b[31m: not found\x1b[0m");
+    /// ```
+    pub fn restyle_range(
+        self,
+        range: std::ops::Range<usize>,
+        style: Style,
+    ) -> Result<StyledLine, ColorError> {
+        let text = &self.string;
+        if range.start > range.end
+            || !text.is_char_boundary(range.start)
+            || !text.is_char_boundary(range.end)
+        {
+            return Err(ColorError::InvalidOperation(
+                "the range must be within the text and on character boundaries",
+            ));
         }
+
+        let pieces = [
+            (&text[..range.start], self.style),
+            (&text[range.clone()], self.style.overlay(style)),
+            (&text[range.end..], self.style),
+        ];
+        Ok(pieces
+            .into_iter()
+            .filter(|(piece, _)| !piece.is_empty())
+            .fold(StyledLine::new(), |line, (piece, style)| {
+                line.push(ColoredString::new(piece, style))
+            }))
     }
 
-    /// Converts a hexadecimal color code (as a string) to a tuple of RGB values.
+    /// Styles each character of the text with a background going from `start` to `end`.
     ///
-    /// This is used internally by the `to_fg` and `to_bg` methods when handling `Color::HEX` variants.
+    /// The foreground of each character is picked with [`Color::best_text_color`] to stay readable.
+    /// Text of a single character takes the `start` color.
     ///
-    /// # Parameters
+    /// # Errors
     ///
-    /// - `hex`: A string slice representing the hexadecimal color code.
+    /// Returns a `ColorError` if a color can not be resolved to RGB (e.g. `Color::Empty`).
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// A tuple of three `u8` values representing the red, green, and blue components of the color, respectively.
+    /// ```
+    /// use inksac::{Color, ColoredString};
     ///
-    fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
-        let hex = hex.strip_prefix('#')?;
+    /// let banner = ColoredString::gradient_bg(" inksac ", Color::RGB(0, 0, 128), Color::RGB(0, 200, 255)).unwrap();
+    /// assert_eq!(banner.segments().len(), 8);
+    /// assert_eq!(banner.segments()[0].style.foreground, Color::White);
+    /// ```
+    pub fn gradient_bg(text: &str, start: Color, end: Color) -> Result<StyledLine, ColorError> {
+        let colors = Gradient::new(vec![start, end])?.sample(text.chars().count())?;
 
-        // if the length of the hex string is not 6, panic the code
-        // Since the terminal does not support `RGBA` colors anyway
-        if hex.len() != 6 {
-            return None;
-        }
+        Ok(text
+            .chars()
+            .zip(colors)
+            .fold(StyledLine::new(), |line, (c, background)| {
+                line.push(c.styled(Style {
+                    foreground: background.best_text_color(),
+                    background,
+                    ..Style::default()
+                }))
+            }))
+    }
 
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    /// Appends text, keeping the style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let mut text = ColoredString::new("", Style::builder().foreground(Color::Red).build());
+    /// for word in ["a", "b"] {
+    ///     text.push_str(word);
+    /// }
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text.to_string(), "\x1b[31mab\x1b[0m");
+    /// ```
+    pub fn push_str(&mut self, s: &str) {
+        self.string.to_mut().push_str(s);
+    }
 
-        Some((r, g, b))
+    /// Appends a character, keeping the style.
+    pub fn push(&mut self, c: char) {
+        self.string.to_mut().push(c);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Removes the leading and trailing whitespace, keeping the style.
+    ///
+    /// Text of only whitespace becomes empty, and still keeps the style.
+    /// These take over the `str` methods of the same name reached through `Deref`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{ColoredString, Style};
+    ///
+    /// let name = ColoredString::new("  alice \n", Style::builder().bold().build());
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(name.trim().to_string(), "\x1b[1malice\x1b[0m");
+    /// ```
+    pub fn trim(&self) -> ColoredString {
+        ColoredString::new(self.string.trim(), self.style)
+    }
 
-    #[test]
-    fn test_hex_to_rgb() {
-        let hex = "#ff0000";
-        let (r, g, b) = Color::hex_to_rgb(hex).unwrap();
-        assert_eq!(r, 255);
-        assert_eq!(g, 0);
-        assert_eq!(b, 0);
+    /// Removes the leading whitespace, keeping the style.
+    pub fn trim_start(&self) -> ColoredString {
+        ColoredString::new(self.string.trim_start(), self.style)
+    }
+
+    /// Removes the trailing whitespace, keeping the style.
+    pub fn trim_end(&self) -> ColoredString {
+        ColoredString::new(self.string.trim_end(), self.style)
+    }
+
+    /// Repeats the text `n` times, keeping the style.
+    ///
+    /// The style wraps the whole repeated block, so it is rendered with a single prefix and reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let bar = ColoredString::new("━", Style::builder().foreground(Color::Green).build());
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(bar.repeat(3).to_string(), "\x1b[32m━━━\x1b[0m");
+    /// ```
+    pub fn repeat(&self, n: usize) -> ColoredString {
+        Self {
+            string: self.string.repeat(n).into(),
+            style: self.style,
+        }
+    }
+
+    /// Applies `f` to the style of the colored string, keeping the same text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::Red).build();
+    /// let text = ColoredString::new("Hello", style).map_style(|style| Style { bold: true, ..style });
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text.to_string(), "\x1b[31;1mHello\x1b[0m");
+    /// ```
+    pub fn map_style(self, f: impl FnOnce(Style) -> Style) -> Self {
+        Self {
+            style: f(self.style),
+            ..self
+        }
+    }
+
+    /// Renders the colored string with the style re-applied on every line.
+    ///
+    /// Every line starts with the style prefix and ends with a reset, so the color survives
+    /// pagers (e.g. `less`) or terminals that clear the formatting at a newline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::Green).build();
+    /// let text = ColoredString::new("one\ntwo", style);
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text.style_each_line(), "\x1b[32mone\x1b[0m\n\x1b[32mtwo\x1b[0m");
+    /// ```
+    pub fn style_each_line(self) -> String {
+        self.lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns an iterator over the lines of the text, each carrying the same style.
+    ///
+    /// The text is split at `\n`, so a trailing newline results in a last empty line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::Blue).build();
+    /// let text = ColoredString::new("top\nbottom", style);
+    ///
+    /// let lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(lines, ["\x1b[34mtop\x1b[0m", "\x1b[34mbottom\x1b[0m"]);
+    /// ```
+    pub fn lines(&self) -> impl Iterator<Item = ColoredString> + '_ {
+        self.string
+            .split('\n')
+            .map(|line| ColoredString::new(line, self.style))
+    }
+
+    /// Wraps the text at word boundaries into lines of at most `width` terminal columns,
+    /// each carrying the same style.
+    ///
+    /// Existing line breaks are kept, runs of whitespace are collapsed to a single space and
+    /// removed at the line ends, and words wider than `width` are broken across lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("styled text wraps", Style::builder().italic().build());
+    ///
+    /// let lines = text.wrap(11);
+    /// assert_eq!(lines[0].as_str(), "styled text");
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(lines[1].to_string(), "\x1b[3mwraps\x1b[0m");
+    /// ```
+    pub fn wrap(&self, width: usize) -> Vec<ColoredString> {
+        width::wrap(&self.string, width)
+            .into_iter()
+            .map(|line| ColoredString {
+                string: line.into(),
+                style: self.style,
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the pieces of the text separated by `pat`, each carrying the same style.
+    ///
+    /// Like [`str::split`], empty pieces are kept, e.g. between two separators.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let text = ColoredString::new("a,b", Style::builder().foreground(Color::Red).build());
+    ///
+    /// let pieces: Vec<String> = text.split(',').map(|piece| piece.to_string()).collect();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(pieces, ["\x1b[31ma\x1b[0m", "\x1b[31mb\x1b[0m"]);
+    /// ```
+    pub fn split(&self, pat: char) -> impl Iterator<Item = ColoredString> + '_ {
+        self.string
+            .split(pat)
+            .map(|piece| ColoredString::new(piece, self.style))
+    }
+}
+
+/// Counts the bytes written through it, without storing them.
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// Writes the styled text, or only the plain text with the alternate flag (`{:#}`).
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, ColoredString, Style};
+///
+/// let text = ColoredString::new("done", Style::builder().foreground(Color::Green).build());
+///
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(format!("{}", text), "\x1b[32mdone\x1b[0m");
+/// assert_eq!(format!("{:#}", text), "done");
+/// ```
+impl fmt::Display for ColoredString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "no-color")
+            || f.alternate()
+            || !styling_enabled()
+            || self.style.is_empty()
+        {
+            return write!(f, "{}", self.string);
+        }
+
+        write!(
+            f,
+            "{}{}{}",
+            self.style,
+            self.string,
+            Style::reset_sequence()
+        )
+    }
+}
+
+/// Gives access to the `str` methods of the underlying text.
+///
+/// Only the plain text is exposed, so `*text` has no escape sequences,
+/// while formatting the `ColoredString` itself with `{}` still writes the style.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Style, Stylish};
+///
+/// let text = "warning: low disk".styled(Style::builder().foreground(Color::Yellow).build());
+///
+/// assert!(text.starts_with("warning"));
+/// assert_eq!(&*text, "warning: low disk");
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_ne!(text.to_string(), "warning: low disk");
+/// ```
+impl std::ops::Deref for ColoredString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.string
+    }
+}
+
+// FIX!: LATER: trait name should be verb
+/// Trait for types that can be styled with a `Style`
+pub trait Stylish {
+    // FIX!: LATER: trait's only method should have consistent name with the trait
+    fn styled(self, style: Style) -> ColoredString;
+
+    /// Styles the text with the given background and a readable foreground.
+    ///
+    /// The foreground is picked with [`Color::best_text_color`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Stylish};
+    ///
+    /// let badge = " WARN ".on(Color::Yellow);
+    /// assert_eq!(badge.style.foreground, Color::Black);
+    /// assert_eq!(badge.style.background, Color::Yellow);
+    /// ```
+    fn on(self, bg: Color) -> ColoredString
+    where
+        Self: Sized,
+    {
+        self.styled(Style {
+            foreground: bg.best_text_color(),
+            background: bg,
+            ..Style::default()
+        })
+    }
+
+    /// Styles every line of the text separately.
+    ///
+    /// Each line gets its own style prefix and reset, so backgrounds do not bleed across line breaks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, Stylish};
+    ///
+    /// let style = Style::builder().background(Color::Blue).build();
+    /// let text = "multi\nline".style_lines(style);
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(text, "\x1b[44mmulti\x1b[0m\n\x1b[44mline\x1b[0m");
+    /// ```
+    fn style_lines(self, style: Style) -> String
+    where
+        Self: Sized,
+    {
+        self.styled(style).style_each_line()
+    }
+}
+
+// FIX: blanket impl for everything that implements `ToString` or `AsRef<str>`
+impl Stylish for String {
+    fn styled(self, style: Style) -> ColoredString {
+        ColoredString::from_cow(Cow::Owned(self), style)
+    }
+}
+
+impl Stylish for &str {
+    fn styled(self, style: Style) -> ColoredString {
+        ColoredString::new(self, style)
+    }
+}
+
+impl Stylish for char {
+    fn styled(self, style: Style) -> ColoredString {
+        ColoredString::new(self.encode_utf8(&mut [0; 4]), style)
+    }
+}
+
+/// Colors the text with the given foreground color, without any other style.
+///
+/// A shorthand for styling with `Style::builder().foreground(fg).build()`.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{colorize, Color};
+///
+/// println!("{}", colorize("done", Color::Green));
+/// ```
+pub fn colorize(text: impl AsRef<str>, fg: Color) -> ColoredString {
+    ColoredString::new(text.as_ref(), Style::builder().foreground(fg).build())
+}
+
+/// Colors the background of the text with the given color, without any other style.
+///
+/// A shorthand for styling with `Style::builder().background(bg).build()`.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{colorize_bg, Color};
+///
+/// println!("{}", colorize_bg(" FAIL ", Color::Red));
+/// ```
+pub fn colorize_bg(text: impl AsRef<str>, bg: Color) -> ColoredString {
+    ColoredString::new(text.as_ref(), Style::builder().background(bg).build())
+}
+
+/// Trait for iterators of strings that can be styled with a `Style`
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, ColoredString, Style, StylishIter};
+///
+/// let style = Style::builder().foreground(Color::Green).build();
+/// let items: Vec<ColoredString> = ["one", "two"].iter().styled(style).collect();
+/// assert_eq!(items.len(), 2);
+/// ```
+pub trait StylishIter: Iterator + Sized {
+    /// Styles every item of the iterator with the same style.
+    fn styled(self, style: Style) -> StyledIter<Self>;
+
+    /// Styles every item of the iterator with the style returned by `f`,
+    /// which is called with the index and the text of the item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, StylishIter};
+    ///
+    /// let even = Style::builder().background(Color::Black).build();
+    /// let odd = Style::builder().background(Color::White).build();
+    ///
+    /// let rows = ["a", "b", "c"]
+    ///     .iter()
+    ///     .styled_with(|i, _| if i % 2 == 0 { even } else { odd });
+    /// for row in rows {
+    ///     println!("{}", row);
+    /// }
+    /// ```
+    fn styled_with<F>(self, f: F) -> StyledWith<Self, F>
+    where
+        F: FnMut(usize, &str) -> Style;
+}
+
+impl<I> StylishIter for I
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    fn styled(self, style: Style) -> StyledIter<Self> {
+        StyledIter { iter: self, style }
+    }
+
+    fn styled_with<F>(self, f: F) -> StyledWith<Self, F>
+    where
+        F: FnMut(usize, &str) -> Style,
+    {
+        StyledWith {
+            iter: self.enumerate(),
+            f,
+        }
+    }
+}
+
+/// An iterator styling every item with the same style, created by [`StylishIter::styled`]
+#[derive(Debug, Clone)]
+pub struct StyledIter<I> {
+    iter: I,
+    style: Style,
+}
+
+impl<I> Iterator for StyledIter<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = ColoredString;
+
+    fn next(&mut self) -> Option<ColoredString> {
+        let item = self.iter.next()?;
+        Some(ColoredString::new(item.as_ref(), self.style))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator styling every item with a computed style, created by [`StylishIter::styled_with`]
+#[derive(Debug, Clone)]
+pub struct StyledWith<I, F> {
+    iter: std::iter::Enumerate<I>,
+    f: F,
+}
+
+impl<I, F> Iterator for StyledWith<I, F>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+    F: FnMut(usize, &str) -> Style,
+{
+    type Item = ColoredString;
+
+    fn next(&mut self) -> Option<ColoredString> {
+        let (index, item) = self.iter.next()?;
+        let text = item.as_ref();
+        Some(ColoredString::new(text, (self.f)(index, text)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A struct representing various styles that can be applied to a string.
+///
+/// Styles include foreground and background color, boldness, dimness, italicization, underlining, and hiding.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Style};
+///
+/// let TITLESTYLE: Style = Style{
+///     foreground: Color::Green,
+///     background: Color::Red,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Style {
+    pub foreground: Color,
+    pub background: Color,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub hidden: bool,
+    /// The color of the underline, only shown while `underline` is set.
+    pub underline_color: Color,
+    /// The shape of the underline, only shown while `underline` is set.
+    pub underline_style: UnderlineStyle,
+    /// Writes the default color codes (`39` and `49`) for the missing colors,
+    /// see [`Style::with_explicit_defaults`].
+    pub explicit_defaults: bool,
+}
+
+/// The shape of an underline
+///
+/// Styled underlines are an extension supported by terminals such as kitty, WezTerm, iTerm2 and the
+/// VTE based ones, using colon separated parameters (e.g. `\x1b[4:3m` for curly).
+/// There is no reliable way to detect the support, so [`Style::downgrade`] falls back to the straight
+/// underline below `ColorSupport::TrueColor`, which modern terminals supporting it all have.
+/// Older terminals may still ignore the whole sequence when they receive one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnderlineStyle {
+    #[default]
+    Straight,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Returns the SGR parameter enabling the underline with this shape.
+    fn sgr_param(self) -> &'static str {
+        match self {
+            UnderlineStyle::Straight => "4",
+            UnderlineStyle::Double => "4:2",
+            UnderlineStyle::Curly => "4:3",
+            UnderlineStyle::Dotted => "4:4",
+            UnderlineStyle::Dashed => "4:5",
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "no-color") || !styling_enabled() {
+            return Ok(());
+        }
+
+        let foreground = self.foreground.sgr_params(false);
+        let background = self.background.sgr_params(true);
+        let mut attributes: Vec<String> = Vec::new();
+        let codes = [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, self.underline_style.sgr_param()),
+            (self.hidden, "8"),
+        ];
+        for (enabled, code) in codes {
+            if enabled {
+                attributes.push(code.to_string());
+            }
+        }
+        if self.underline {
+            attributes.extend(self.underline_color.underline_params());
+        }
+
+        if foreground.is_none() && background.is_none() && attributes.is_empty() {
+            return Ok(());
+        }
+
+        // every parameter goes into a single sequence, e.g. `\x1b[31;44;1m`
+        let or_default = |params: Option<String>, code: &str| {
+            params.or_else(|| self.explicit_defaults.then(|| code.to_string()))
+        };
+        let params: Vec<String> = [
+            or_default(foreground, ansi_base::RESET_FOREGROUND_PARAM),
+            or_default(background, ansi_base::RESET_BACKGROUND_PARAM),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(attributes)
+        .collect();
+        f.write_str(&ansi_base::sgr_str(&params))
+    }
+}
+
+impl Style {
+    /// Creates a new instance of `StyleBuilder` with default values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Style;
+    ///
+    /// let builder = Style::builder();
+    /// ```
+    pub fn builder() -> StyleBuilder {
+        StyleBuilder::default()
+    }
+
+    /// Returns the opening escape sequences of the style.
+    ///
+    /// This is what gets written before the text when displaying a `ColoredString`,
+    /// and is equivalent to `style.to_string()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColoredString, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::Red).build();
+    /// let rendered = format!("{}{}{}", style.prefix(), "Hello", Style::reset_sequence());
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(rendered, ColoredString::new("Hello", style).to_string());
+    /// ```
+    pub fn prefix(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the escape sequence that resets all styles.
+    ///
+    /// This is [`ansi_base::RESET`] unless another sequence was chosen with [`set_reset_sequence`].
+    pub fn reset_sequence() -> &'static str {
+        if SHORT_RESET.load(Ordering::Relaxed) {
+            ResetSequence::Short.as_str()
+        } else {
+            ResetSequence::Full.as_str()
+        }
+    }
+
+    /// Returns `true` if the style has no colors and no attributes set.
+    ///
+    /// An underline color or shape without the underline is not shown, so neither counts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Style;
+    ///
+    /// assert!(Style::default().is_empty());
+    /// assert!(!Style::builder().hidden().build().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.foreground == Color::Empty
+            && self.background == Color::Empty
+            && !self.bold
+            && !self.dim
+            && !self.italic
+            && !self.underline
+            && !self.hidden
+    }
+
+    /// Returns the escape sequences that switch from this style to the `next` one.
+    ///
+    /// Only the colors and attributes that change are emitted, and attributes are turned off
+    /// one by one instead of resetting everything, so the result is empty if both styles are the same.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let from = Style::builder().foreground(Color::Red).bold().build();
+    /// let to = Style::builder().foreground(Color::Red).italic().build();
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(from.diff(&to), "\x1b[22;3m");
+    /// assert_eq!(to.diff(&to), "");
+    /// ```
+    pub fn diff(&self, next: &Style) -> String {
+        if cfg!(feature = "no-color") || !styling_enabled() {
+            return String::new();
+        }
+
+        let mut params: Vec<String> = Vec::new();
+
+        if self.foreground != next.foreground {
+            params.push(
+                next.foreground
+                    .sgr_params(false)
+                    .unwrap_or(ansi_base::RESET_FOREGROUND_PARAM.to_string()),
+            );
+        }
+        if self.background != next.background {
+            params.push(
+                next.background
+                    .sgr_params(true)
+                    .unwrap_or(ansi_base::RESET_BACKGROUND_PARAM.to_string()),
+            );
+        }
+
+        // bold and dim share a single reset code, so both are re-applied after it
+        let bold_dim_reset = (self.bold && !next.bold) || (self.dim && !next.dim);
+        if bold_dim_reset {
+            params.push("22".to_string());
+        }
+        let toggles = [
+            (self.bold && !bold_dim_reset, next.bold, "1", None),
+            (self.dim && !bold_dim_reset, next.dim, "2", None),
+            (self.italic, next.italic, "3", Some("23")),
+            (
+                self.underline,
+                next.underline,
+                next.underline_style.sgr_param(),
+                Some("24"),
+            ),
+            (self.hidden, next.hidden, "8", Some("28")),
+        ];
+        for (from, to, on, off) in toggles {
+            match (from, to, off) {
+                (false, true, _) => params.push(on.to_string()),
+                (true, false, Some(off)) => params.push(off.to_string()),
+                _ => {}
+            }
+        }
+
+        if self.underline && next.underline && self.underline_style != next.underline_style {
+            params.push(next.underline_style.sgr_param().to_string());
+        }
+
+        let (from_color, to_color) = (self.shown_underline_color(), next.shown_underline_color());
+        if from_color != to_color {
+            params.push(to_color.underline_params().unwrap_or("59".to_string()));
+        }
+
+        ansi_base::sgr_str(&params)
+    }
+
+    /// Returns the underline color, or `Color::Empty` if the text is not underlined.
+    fn shown_underline_color(&self) -> Color {
+        if self.underline {
+            self.underline_color
+        } else {
+            Color::Empty
+        }
+    }
+
+    /// Returns `false` if the foreground can not be told apart from the background.
+    ///
+    /// The colors are too close when their contrast ratio is below 1.2, e.g. the same color twice.
+    /// A style missing either color is always visible, since the terminal default fills it in.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if a color can not be resolved to RGB (e.g. an invalid `Color::HEX`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let hidden = Style::builder().foreground(Color::Red).background(Color::Red).build();
+    /// assert!(!hidden.is_visible().unwrap());
+    /// ```
+    pub fn is_visible(&self) -> Result<bool, ColorError> {
+        if self.foreground == Color::Empty || self.background == Color::Empty {
+            return Ok(true);
+        }
+
+        Ok(self.foreground.contrast_ratio(self.background)? >= 1.2)
+    }
+
+    /// Writes the text with this style to the writer, downgraded to the color support of stdout.
+    ///
+    /// The style, the text and a reset are written in one call, without building a [`ColoredString`].
+    /// The support is detected with [`check_color_support`] on every call, so prefer
+    /// [`ColoredString::render_with`] or [`StylePrefix`] with a known support level in hot loops.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let warning = Style::builder().foreground(Color::Yellow).build();
+    /// warning.apply(&mut std::io::stderr(), "careful\n").unwrap();
+    /// ```
+    pub fn apply<W: std::io::Write>(&self, w: &mut W, text: &str) -> std::io::Result<()> {
+        let support = check_color_support();
+        let style = self.downgrade(support);
+        if support == ColorSupport::NoColor
+            || cfg!(feature = "no-color")
+            || !styling_enabled()
+            || style.is_empty()
+        {
+            return w.write_all(text.as_bytes());
+        }
+
+        write!(w, "{}{}{}", style, text, Style::reset_sequence())
+    }
+
+    /// Sets whether the missing colors are written as the default color codes.
+    ///
+    /// A style without a foreground normally leaves the foreground of the preceding output active,
+    /// e.g. when it is written after text whose reset was cut off. With explicit defaults,
+    /// the missing colors are written as `39` (default foreground) and `49` (default background),
+    /// as long as the style writes anything at all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let style = Style::builder().background(Color::Blue).build();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(style.to_string(), "\x1b[44m");
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(style.with_explicit_defaults(true).to_string(), "\x1b[39;44m");
+    /// ```
+    pub fn with_explicit_defaults(self, explicit_defaults: bool) -> Style {
+        Style {
+            explicit_defaults,
+            ..self
+        }
+    }
+
+    /// Lays the `top` style over this one.
+    ///
+    /// The colors of `top` win unless they are `Color::Empty`, and the attributes of both are combined.
+    /// Unlike [`Style::merge`], nothing can be turned off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let base = Style::builder().foreground(Color::White).background(Color::Blue).build();
+    /// let highlight = Style::builder().foreground(Color::Yellow).bold().build();
+    ///
+    /// assert_eq!(
+    ///     base.overlay(highlight),
+    ///     Style::builder().foreground(Color::Yellow).background(Color::Blue).bold().build()
+    /// );
+    /// ```
+    pub fn overlay(self, top: Style) -> Style {
+        let pick = |base: Color, top: Color| if top == Color::Empty { base } else { top };
+        let underline_style = if top.underline {
+            top.underline_style
+        } else {
+            self.underline_style
+        };
+
+        Style {
+            foreground: pick(self.foreground, top.foreground),
+            background: pick(self.background, top.background),
+            bold: self.bold || top.bold,
+            dim: self.dim || top.dim,
+            italic: self.italic || top.italic,
+            underline: self.underline || top.underline,
+            hidden: self.hidden || top.hidden,
+            underline_color: pick(self.underline_color, top.underline_color),
+            underline_style,
+            explicit_defaults: self.explicit_defaults || top.explicit_defaults,
+        }
+    }
+
+    /// Applies the fields set in the partial style on top of this style.
+    ///
+    /// See [`PartialStyle`] for the override rules.
+    pub fn merge(self, other: PartialStyle) -> Style {
+        Style {
+            foreground: other.foreground.unwrap_or(self.foreground),
+            background: other.background.unwrap_or(self.background),
+            bold: other.bold.unwrap_or(self.bold),
+            dim: other.dim.unwrap_or(self.dim),
+            italic: other.italic.unwrap_or(self.italic),
+            underline: other.underline.unwrap_or(self.underline),
+            hidden: other.hidden.unwrap_or(self.hidden),
+            underline_color: other.underline_color.unwrap_or(self.underline_color),
+            underline_style: other.underline_style.unwrap_or(self.underline_style),
+            explicit_defaults: self.explicit_defaults,
+        }
+    }
+
+    /// Returns a partial style setting every field, so merging it replaces the whole base style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let theme = Style::builder().foreground(Color::Cyan).underline().build();
+    /// let base = Style::builder().background(Color::Black).bold().build();
+    ///
+    /// assert_eq!(base.merge(theme.to_partial()), theme);
+    /// ```
+    pub fn to_partial(self) -> PartialStyle {
+        PartialStyle {
+            foreground: Some(self.foreground),
+            background: Some(self.background),
+            bold: Some(self.bold),
+            dim: Some(self.dim),
+            italic: Some(self.italic),
+            underline: Some(self.underline),
+            hidden: Some(self.hidden),
+            underline_color: Some(self.underline_color),
+            underline_style: Some(self.underline_style),
+        }
+    }
+
+    /// Returns the style with the foreground and background colors exchanged.
+    ///
+    /// Unlike the reverse video attribute of the terminal, the swap is baked into the color codes,
+    /// so it survives copying the text along with its escape sequences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let style = Style::builder().foreground(Color::White).background(Color::Blue).build();
+    /// let selected = style.swap_colors();
+    ///
+    /// assert_eq!(selected.foreground, Color::Blue);
+    /// assert_eq!(selected.background, Color::White);
+    /// ```
+    pub fn swap_colors(self) -> Style {
+        Style {
+            foreground: self.background,
+            background: self.foreground,
+            ..self
+        }
+    }
+
+    /// Downgrades the colors of the style to the given support level.
+    ///
+    /// See [`Color::downgrade`] for how the colors are converted.
+    /// Terminals with only basic colors do not support underline colors, so it is removed,
+    /// and the styled underlines fall back to straight below true color support (see [`UnderlineStyle`]).
+    pub fn downgrade(self, support: ColorSupport) -> Style {
+        let underline_color = if support >= ColorSupport::Color256 {
+            self.underline_color.downgrade(support)
+        } else {
+            Color::Empty
+        };
+        let underline_style = if support >= ColorSupport::TrueColor {
+            self.underline_style
+        } else {
+            UnderlineStyle::Straight
+        };
+
+        Style {
+            foreground: self.foreground.downgrade(support),
+            background: self.background.downgrade(support),
+            underline_color,
+            underline_style,
+            ..self
+        }
+    }
+}
+
+/// The escape sequence of a style, rendered once and reused
+///
+/// Displaying a `Style` formats its escape sequence every time, which adds up in render loops
+/// styling many strings the same way. `StylePrefix` renders it once for a support level,
+/// and [`StylePrefix::wrap`] only concatenates.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, ColoredString, ColorSupport, Style, StylePrefix};
+///
+/// let style = Style::builder().foreground(Color::RGB(255, 0, 0)).build();
+/// let prefix = StylePrefix::new(style, ColorSupport::Color256);
+///
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(prefix.wrap("hot"), "\x1b[38;5;196mhot\x1b[0m");
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(
+///     prefix.wrap("hot"),
+///     ColoredString::new("hot", style).render_with(ColorSupport::Color256)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StylePrefix {
+    prefix: String,
+}
+
+impl StylePrefix {
+    /// Renders the escape sequence of the style, downgraded to the given support level.
+    pub fn new(style: Style, support: ColorSupport) -> Self {
+        let prefix = if support == ColorSupport::NoColor {
+            String::new()
+        } else {
+            style.downgrade(support).to_string()
+        };
+
+        Self { prefix }
+    }
+
+    /// Returns the rendered escape sequence.
+    pub fn as_str(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Wraps the text in the escape sequence and a reset.
+    ///
+    /// The text is returned unchanged if the prefix is empty or styling is disabled (see [`set_styling_enabled`]).
+    pub fn wrap(&self, text: &str) -> String {
+        if self.prefix.is_empty() || !styling_enabled() {
+            return text.to_string();
+        }
+
+        let reset = Style::reset_sequence();
+        let mut out = String::with_capacity(self.prefix.len() + text.len() + reset.len());
+        out.push_str(&self.prefix);
+        out.push_str(text);
+        out.push_str(reset);
+        out
+    }
+}
+
+// FIX!: unnecessary builder pattern
+/// A builder struct for constructing a `Style` instance with various configurations.
+pub struct StyleBuilder {
+    style: Style,
+}
+
+impl Default for StyleBuilder {
+    /// Constructs a new `StyleBuilder` with a default `Style`.
+    fn default() -> Self {
+        Self {
+            style: Style::default(),
+        }
+    }
+}
+
+impl StyleBuilder {
+    /// Sets the foreground color of the style.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - An option containing a `Color` enum variant to set as the foreground color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .foreground(Color::Green)
+    ///     .build();
+    /// ```
+    pub fn foreground(mut self, color: Color) -> Self {
+        // FIX!: ASAP: take & return mutable reference rather than taking ownership
+        // | e.g. (&mut self, color: Color) -> &mut Self
+        // | also applys to every builder pattern methods below
+        self.style.foreground = color;
+        self
+    }
+
+    /// Sets the background color of the style.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - An option containing a `Color` enum variant to set as the background color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .background(Color::Red)
+    ///     .build();
+    /// ```
+    pub fn background(mut self, color: Color) -> Self {
+        self.style.background = color;
+        self
+    }
+
+    /// Sets the foreground color of the style from RGB values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .foreground_rgb(255, 128, 0)
+    ///     .build();
+    /// assert_eq!(style.foreground, Color::RGB(255, 128, 0));
+    /// ```
+    pub fn foreground_rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.foreground(Color::RGB(r, g, b))
+    }
+
+    /// Sets the background color of the style from RGB values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .background_rgb(0, 0, 128)
+    ///     .build();
+    /// assert_eq!(style.background, Color::RGB(0, 0, 128));
+    /// ```
+    pub fn background_rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.background(Color::RGB(r, g, b))
+    }
+
+    /// Sets the foreground color of the style from a hexadecimal color code.
+    ///
+    /// The code is parsed once and stored as `Color::RGB`, and unlike `Color::HEX` it does not need to be `'static`.
+    /// An invalid code leaves the foreground unset (`Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .foreground_hex("#ff8000")
+    ///     .build();
+    /// assert_eq!(style.foreground, Color::RGB(255, 128, 0));
+    /// ```
+    pub fn foreground_hex(self, hex: &str) -> Self {
+        self.foreground(Color::hex_to_color(hex))
+    }
+
+    /// Sets the background color of the style from a hexadecimal color code.
+    ///
+    /// See [`StyleBuilder::foreground_hex`] for how the code is handled.
+    pub fn background_hex(self, hex: &str) -> Self {
+        self.background(Color::hex_to_color(hex))
+    }
+
+    /// Shorthand for [`StyleBuilder::foreground`].
+    pub fn fg(self, color: Color) -> Self {
+        self.foreground(color)
+    }
+
+    /// Shorthand for [`StyleBuilder::background`].
+    pub fn bg(self, color: Color) -> Self {
+        self.background(color)
+    }
+
+    /// Sets the bright variant of a predefined color as the foreground color of the style.
+    ///
+    /// Colors without a bright variant (e.g. `Color::RGB`) are set as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder, Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .bright_foreground(Color::Red)
+    ///     .build();
+    /// assert_eq!(style.foreground, Color::BrightRed);
+    /// ```
+    pub fn bright_foreground(mut self, color: Color) -> Self {
+        self.style.foreground = color.to_bright();
+        self
+    }
+
+    /// Sets the bold attribute of the style to true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .bold()
+    ///     .build();
+    /// ```
+    pub fn bold(mut self) -> Self {
+        self.style.bold = true;
+        self
+    }
+
+    /// Sets the dim attribute of the style to true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .dim()
+    ///     .build();
+    /// ```
+    pub fn dim(mut self) -> Self {
+        self.style.dim = true;
+        self
+    }
+
+    /// Sets the italic attribute of the style to true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .italic()
+    ///     .build();
+    /// ```
+    pub fn italic(mut self) -> Self {
+        self.style.italic = true;
+        self
+    }
+
+    /// Sets the underline attribute of the style to true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .underline()
+    ///     .build();
+    /// ```
+    pub fn underline(mut self) -> Self {
+        self.style.underline = true;
+        self
+    }
+
+    /// Sets the color of the underline.
+    ///
+    /// The color is only shown on underlined text, and requires a terminal supporting underline colors
+    /// (e.g. kitty, WezTerm or VTE based terminals), others ignore it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let style = Style::builder()
+    ///     .underline()
+    ///     .underline_color(Color::RGB(255, 0, 0))
+    ///     .build();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(style.to_string(), "\x1b[4;58;2;255;0;0m");
+    /// ```
+    pub fn underline_color(mut self, color: Color) -> Self {
+        self.style.underline_color = color;
+        self
+    }
+
+    /// Sets the shape of the underline.
+    ///
+    /// Like the color, the shape is only shown on underlined text. See [`UnderlineStyle`] for the terminal support.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Style, UnderlineStyle};
+    ///
+    /// let style = Style::builder()
+    ///     .underline()
+    ///     .underline_style(UnderlineStyle::Curly)
+    ///     .build();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(style.to_string(), "\x1b[4:3m");
+    /// ```
+    pub fn underline_style(mut self, underline_style: UnderlineStyle) -> Self {
+        self.style.underline_style = underline_style;
+        self
+    }
+
+    /// Sets the hidden attribute of the style to true.
+    ///
+    /// Hidden (concealed) text is not displayed, which is useful for passwords or spoilers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .hidden()
+    ///     .build();
+    /// ```
+    pub fn hidden(mut self) -> Self {
+        self.style.hidden = true;
+        self
+    }
+
+    /// Builds and returns a `Style` instance with the configurations set in the builder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{StyleBuilder,Color};
+    ///
+    /// let style = StyleBuilder::default()
+    ///     .foreground(Color::Green)
+    ///     .bold()
+    ///     .build();
+    /// ```
+    pub fn build(self) -> Style {
+        self.style
+    }
+}
+
+/// A style where every field is optional, for layering styles on top of each other
+///
+/// Applied with [`Style::merge`], `Some` overrides the field of the base style, including
+/// turning an attribute off with `Some(false)`, while `None` leaves it untouched.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, PartialStyle, Style};
+///
+/// let base = Style::builder().foreground(Color::Red).bold().build();
+/// let layer = PartialStyle::new().bold(false).italic(true);
+///
+/// let merged = base.merge(layer);
+/// assert_eq!(merged, Style::builder().foreground(Color::Red).italic().build());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PartialStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: Option<bool>,
+    pub dim: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub hidden: Option<bool>,
+    pub underline_color: Option<Color>,
+    pub underline_style: Option<UnderlineStyle>,
+}
+
+impl PartialStyle {
+    /// Creates a partial style leaving every field untouched.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the foreground color, `Color::Empty` removes it.
+    pub fn foreground(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// Overrides the background color, `Color::Empty` removes it.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Turns the bold attribute on or off.
+    pub fn bold(mut self, enabled: bool) -> Self {
+        self.bold = Some(enabled);
+        self
+    }
+
+    /// Turns the dim attribute on or off.
+    pub fn dim(mut self, enabled: bool) -> Self {
+        self.dim = Some(enabled);
+        self
+    }
+
+    /// Turns the italic attribute on or off.
+    pub fn italic(mut self, enabled: bool) -> Self {
+        self.italic = Some(enabled);
+        self
+    }
+
+    /// Turns the underline attribute on or off.
+    pub fn underline(mut self, enabled: bool) -> Self {
+        self.underline = Some(enabled);
+        self
+    }
+
+    /// Turns the hidden attribute on or off.
+    pub fn hidden(mut self, enabled: bool) -> Self {
+        self.hidden = Some(enabled);
+        self
+    }
+
+    /// Overrides the underline color, `Color::Empty` removes it.
+    pub fn underline_color(mut self, color: Color) -> Self {
+        self.underline_color = Some(color);
+        self
+    }
+
+    /// Overrides the shape of the underline.
+    pub fn underline_style(mut self, underline_style: UnderlineStyle) -> Self {
+        self.underline_style = Some(underline_style);
+        self
+    }
+}
+
+/// Represents the different colors that can be used for text foreground and background styling.
+///
+/// The enum provides several options to specify colors:
+/// - Predefined color values (e.g., `Black`, `Red`, `Green`, etc.)
+/// - Bright variants of the predefined colors (e.g., `BrightBlack`, `BrightRed`, etc.)
+/// - RGB values with the `RGB` variant
+/// - Hexadecimal color codes with the `HEX` variant
+/// - 256 color palette codes with the `Color256` variant
+/// - RGB values with an alpha channel with the `RGBA` variant
+///
+/// # Examples
+///
+/// Using predefined color values:
+///
+/// ```
+/// use inksac::Color;
+///
+/// let red = Color::Red;
+/// let green = Color::Green;
+/// ```
+///
+/// Using RGB values:
+///
+/// ```
+/// use inksac::Color;
+///
+/// let custom_color = Color::RGB(128, 0, 128);
+/// ```
+///
+/// Using a hexadecimal color code:
+///
+/// ```
+/// use inksac::Color;
+///
+/// let custom_color = Color::HEX("#800080");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    /// Represents an absence of color.
+    #[default]
+    Empty,
+
+    /// Specifies a color using RGB values.
+    RGB(u8, u8, u8),
+
+    /// Specifies a color using a hexadecimal color code.
+    ///
+    /// The code is only parsed when the color is used, and an invalid code renders as no color.
+    /// Use [`Color::hex`] to validate it upfront.
+    HEX(&'static str),
+
+    /// Specifies a color from the 256 color palette.
+    Color256(u8),
+
+    /// Specifies a semi-transparent color using RGB values and an alpha channel.
+    ///
+    /// Terminals have no transparency, so on its own it is composited over black.
+    /// Use [`Color::composite_over`] to composite it over a known background.
+    RGBA(u8, u8, u8, u8),
+}
+
+/// How the distance between two colors is measured, see [`Color::distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DistanceMetric {
+    /// The straight line distance between the RGB values, from `0.0` to about `441.7`.
+    #[default]
+    Euclidean,
+    /// The "redmean" approximation, weighting the RGB channels by how sensitive the eye is to them.
+    WeightedEuclidean,
+    /// The CIE76 color difference in the CIELAB color space, where about `2.3` is just noticeable.
+    DeltaE76,
+}
+
+/// The RGB values of the 256 color palette, see [`Color::palette_256`].
+const PALETTE_256: [(u8, u8, u8); 256] = build_palette_256();
+
+const fn build_palette_256() -> [(u8, u8, u8); 256] {
+    // the default xterm values of the system colors, in the order of `Color::SYSTEM_COLORS`
+    const SYSTEM: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const fn level(v: u8) -> u8 {
+        if v == 0 {
+            0
+        } else {
+            55 + v * 40
+        }
+    }
+
+    let mut palette = [(0, 0, 0); 256];
+    let mut code = 0;
+    while code < 256 {
+        palette[code] = match code {
+            0..=15 => SYSTEM[code],
+            16..=231 => {
+                let index = (code - 16) as u8;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            _ => {
+                let gray = 8 + (code - 232) as u8 * 10;
+                (gray, gray, gray)
+            }
+        };
+        code += 1;
+    }
+    palette
+}
+
+impl Color {
+    /// Returns the SGR parameters selecting the color (e.g. `31` or `38;2;r;g;b`),
+    /// or `None` for `Color::Empty` and invalid HEX codes.
+    ///
+    /// The background codes are the foreground ones shifted by 10.
+    fn sgr_params(self, background: bool) -> Option<String> {
+        let offset = if background { 10 } else { 0 };
+        let code = match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::BrightBlack => 90,
+            Color::BrightRed => 91,
+            Color::BrightGreen => 92,
+            Color::BrightYellow => 93,
+            Color::BrightBlue => 94,
+            Color::BrightMagenta => 95,
+            Color::BrightCyan => 96,
+            Color::BrightWhite => 97,
+            Color::Empty => return None,
+            Color::RGB(r, g, b) => return Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+            Color::HEX(code) => {
+                // an invalid code renders like `Color::Empty` instead of aborting the output
+                let (r, g, b) = Self::hex_to_rgb(code)?;
+                return Some(format!("{};2;{};{};{}", 38 + offset, r, g, b));
+            }
+            Color::Color256(code) => return Some(format!("{};5;{}", 38 + offset, code)),
+            Color::RGBA(..) => {
+                let (r, g, b) = self.composite_over_black();
+                return Some(format!("{};2;{};{};{}", 38 + offset, r, g, b));
+            }
+        };
+
+        Some((code + offset).to_string())
+    }
+
+    /// Returns the SGR parameters selecting the color as the underline color (e.g. `58;5;n` or `58;2;r;g;b`),
+    /// or `None` for `Color::Empty` and invalid HEX codes.
+    ///
+    /// There are no basic codes for underline colors, so the predefined colors use their 256 color palette code.
+    fn underline_params(self) -> Option<String> {
+        match self {
+            Color::Empty => None,
+            Color::Color256(code) => Some(format!("58;5;{}", code)),
+            Color::RGB(..) | Color::RGBA(..) | Color::HEX(_) => {
+                let (r, g, b) = self.to_rgb().ok()?;
+                Some(format!("58;2;{};{};{}", r, g, b))
+            }
+            _ => {
+                let code = Self::SYSTEM_COLORS
+                    .iter()
+                    .position(|&color| color == self)?;
+                Some(format!("58;5;{}", code))
+            }
+        }
+    }
+
+    /// Converts a hexadecimal color code (as a string) to a tuple of RGB values.
+    ///
+    /// This is used internally when rendering `Color::HEX` variants.
+    ///
+    /// # Parameters
+    ///
+    /// - `hex`: A string slice representing the hexadecimal color code, in the `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` form.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of three `u8` values representing the red, green, and blue components of the color, respectively.
+    /// The alpha component is ignored, since terminals do not support transparency anyway.
+    ///
+    fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+        let hex = hex.strip_prefix('#')?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        match hex.len() {
+            // shorthand forms, each digit is repeated (`#F80` is `#FF8800`)
+            3 | 4 => {
+                let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+                Some((digit(0)?, digit(1)?, digit(2)?))
+            }
+            6 | 8 => {
+                let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+                Some((pair(0)?, pair(2)?, pair(4)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a hexadecimal color code into `Color::RGB`, or `Color::Empty` if it is invalid.
+    fn hex_to_color(hex: &str) -> Color {
+        match Self::hex_to_rgb(hex) {
+            Some((r, g, b)) => Color::RGB(r, g, b),
+            None => Color::Empty,
+        }
+    }
+
+    /// Returns the bright variant of a predefined color, other colors are returned unchanged.
+    fn to_bright(self) -> Color {
+        match self {
+            Color::Black => Color::BrightBlack,
+            Color::Red => Color::BrightRed,
+            Color::Green => Color::BrightGreen,
+            Color::Yellow => Color::BrightYellow,
+            Color::Blue => Color::BrightBlue,
+            Color::Magenta => Color::BrightMagenta,
+            Color::Cyan => Color::BrightCyan,
+            Color::White => Color::BrightWhite,
+            other => other,
+        }
+    }
+
+    /// Resolves the color to its RGB components.
+    ///
+    /// The predefined colors are mapped through the default xterm palette.
+    fn to_rgb(self) -> Result<(u8, u8, u8), ColorError> {
+        match self {
+            Color::Empty => Err(ColorError::InvalidOperation(
+                "Color::Empty has no RGB value",
+            )),
+            Color::RGB(r, g, b) => Ok((r, g, b)),
+            Color::HEX(code) => {
+                Self::hex_to_rgb(code).ok_or_else(|| ColorError::InvalidHexCode(code.to_string()))
+            }
+            Color::Color256(code) => Ok(Self::code_to_rgb(code)),
+            Color::RGBA(..) => Ok(self.composite_over_black()),
+            _ => {
+                // every other variant is one of the predefined colors
+                let code = Self::SYSTEM_COLORS
+                    .iter()
+                    .position(|&color| color == self)
+                    .unwrap_or(0);
+                Ok(PALETTE_256[code])
+            }
+        }
+    }
+
+    /// Composites an `RGBA` color over black, other colors are resolved as is.
+    fn composite_over_black(self) -> (u8, u8, u8) {
+        match self.composite_over(Color::RGB(0, 0, 0)) {
+            Ok(Color::RGB(r, g, b)) => (r, g, b),
+            _ => (0, 0, 0),
+        }
+    }
+
+    /// Returns the RGB components of the color, or `None` for `Color::Empty` and invalid HEX codes.
+    ///
+    /// The predefined colors are mapped through the default xterm palette,
+    /// and `Color::RGBA` is composited over black like when it is rendered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::HEX("#ff8000").to_rgb_tuple(), Some((255, 128, 0)));
+    /// assert_eq!(Color::Color256(196).to_rgb_tuple(), Some((255, 0, 0)));
+    /// assert_eq!(Color::Empty.to_rgb_tuple(), None);
+    /// ```
+    pub fn to_rgb_tuple(self) -> Option<(u8, u8, u8)> {
+        self.to_rgb().ok()
+    }
+
+    /// Returns the color as a `#RRGGBB` hexadecimal code, or `None` if it has no RGB value.
+    ///
+    /// See [`Color::to_rgb_tuple`] for how the color is resolved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::Red.to_hex_string(), Some("#CD0000".to_string()));
+    /// assert_eq!(Color::RGB(255, 128, 0).to_hex_string(), Some("#FF8000".to_string()));
+    /// ```
+    pub fn to_hex_string(self) -> Option<String> {
+        let (r, g, b) = self.to_rgb_tuple()?;
+        Some(format!("#{:02X}{:02X}{:02X}", r, g, b))
+    }
+
+    /// Creates a `Color::RGB` from a packed `0xRRGGBB` value.
+    ///
+    /// Only the low 24 bits are used, the high byte (e.g. an alpha channel) is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::from_u32(0xFF8000), Color::RGB(255, 128, 0));
+    /// assert_eq!(Color::from_u32(0x80FF8000), Color::RGB(255, 128, 0));
+    /// ```
+    pub fn from_u32(value: u32) -> Color {
+        let [_, r, g, b] = value.to_be_bytes();
+        Color::RGB(r, g, b)
+    }
+
+    /// Returns the color packed as `0xRRGGBB`, or `None` if it has no RGB value.
+    ///
+    /// See [`Color::to_rgb_tuple`] for how the color is resolved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::RGB(255, 128, 0).to_u32(), Some(0xFF8000));
+    /// assert_eq!(Color::Color256(196).to_u32(), Some(0xFF0000));
+    /// assert_eq!(Color::Empty.to_u32(), None);
+    /// ```
+    pub fn to_u32(self) -> Option<u32> {
+        let (r, g, b) = self.to_rgb_tuple()?;
+        Some(u32::from_be_bytes([0, r, g, b]))
+    }
+
+    /// Alpha blends the color over a background color.
+    ///
+    /// Each channel is blended as `src * alpha + dst * (1 - alpha)`, resulting in an opaque `Color::RGB`.
+    /// Colors other than `Color::RGBA` are treated as fully opaque.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let overlay = Color::RGBA(255, 0, 0, 128);
+    /// let pink = overlay.composite_over(Color::RGB(255, 255, 255)).unwrap();
+    /// assert_eq!(pink, Color::RGB(255, 127, 127));
+    /// ```
+    pub fn composite_over(self, bg: Color) -> Result<Color, ColorError> {
+        let (dr, dg, db) = bg.to_rgb()?;
+        let (sr, sg, sb, alpha) = match self {
+            Color::RGBA(r, g, b, a) => (r, g, b, a),
+            _ => {
+                let (r, g, b) = self.to_rgb()?;
+                (r, g, b, 255)
+            }
+        };
+
+        let alpha = alpha as f32 / 255.0;
+        let blend =
+            |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+
+        Ok(Color::RGB(blend(sr, dr), blend(sg, dg), blend(sb, db)))
+    }
+
+    /// Resolves the color to RGB for the arithmetic operators, which can not return errors.
+    fn to_rgb_or_panic(self) -> (u8, u8, u8) {
+        self.to_rgb().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns the predefined color of an SGR foreground code (30-37 and 90-97).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::from_fg_code(31), Some(Color::Red));
+    /// assert_eq!(Color::from_fg_code(91), Some(Color::BrightRed));
+    /// assert_eq!(Color::from_fg_code(41), None);
+    /// ```
+    pub fn from_fg_code(code: u8) -> Option<Color> {
+        match code {
+            30..=37 => Some(Self::SYSTEM_COLORS[code as usize - 30]),
+            90..=97 => Some(Self::SYSTEM_COLORS[code as usize - 82]),
+            _ => None,
+        }
+    }
+
+    /// Returns the predefined color of an SGR background code (40-47 and 100-107).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::from_bg_code(44), Some(Color::Blue));
+    /// assert_eq!(Color::from_bg_code(104), Some(Color::BrightBlue));
+    /// ```
+    pub fn from_bg_code(code: u8) -> Option<Color> {
+        code.checked_sub(10).and_then(Self::from_fg_code)
+    }
+
+    /// The predefined colors in the order of their 256 color palette codes (0-15).
+    const SYSTEM_COLORS: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+
+    /// Converts a 256 color palette code to its RGB values.
+    fn code_to_rgb(code: u8) -> (u8, u8, u8) {
+        PALETTE_256[code as usize]
+    }
+
+    /// Returns the RGB values of the 256 color palette, indexed by the palette code.
+    ///
+    /// The system colors (0-15) use the default xterm values, followed by the 6x6x6 color cube (16-231)
+    /// and the grayscale ramp (232-255). Terminals may redefine the system colors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let palette = Color::palette_256();
+    /// assert_eq!(palette[196], (255, 0, 0));
+    /// assert_eq!(palette[244], (128, 128, 128));
+    /// ```
+    pub fn palette_256() -> &'static [(u8, u8, u8); 256] {
+        &PALETTE_256
+    }
+
+    /// Converts RGB values to the closest code of the 256 color palette.
+    ///
+    /// Only the 6x6x6 color cube (16-231) and the grayscale ramp (232-255) are used,
+    /// since the system colors (0-15) may be redefined by the terminal.
+    /// Grays pick the closest of the ramp, black (16) and white (231).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::rgb_to_256(255, 0, 0), 196);
+    /// assert_eq!(Color::rgb_to_256(0, 0, 0), 16);
+    /// ```
+    pub fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+        if r == g && g == b {
+            // the ramp runs from 8 to 238 in steps of 10, with black and white from the cube around it
+            return std::iter::once(16)
+                .chain(232..=255)
+                .chain(std::iter::once(231))
+                .min_by_key(|&code| Self::code_to_rgb(code).0.abs_diff(r))
+                .unwrap_or(16);
+        }
+
+        let to_level = |v: u8| match v {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (v - 35) / 40,
+        };
+
+        16 + 36 * to_level(r) + 6 * to_level(g) + to_level(b)
+    }
+
+    /// Converts RGB values to the closest of the 8 basic ANSI colors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::rgb_to_basic(250, 10, 10), Color::Red);
+    /// ```
+    pub fn rgb_to_basic(r: u8, g: u8, b: u8) -> Color {
+        // the palette only holds predefined colors, so this never fails
+        Color::RGB(r, g, b)
+            .nearest_in(&Self::SYSTEM_COLORS[..8])
+            .unwrap_or(Color::White)
+    }
+
+    /// Converts a 256 color palette code to the closest predefined color.
+    ///
+    /// The 16 system codes map directly to their predefined color, the grayscale ramp (232-255)
+    /// maps to black or white depending on its luminance, and the others go through their RGB value
+    /// and [`Color::rgb_to_basic`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::code_256_to_basic(9), Color::BrightRed);
+    /// assert_eq!(Color::code_256_to_basic(196), Color::Red);
+    /// ```
+    pub fn code_256_to_basic(code: u8) -> Color {
+        match code {
+            0..=15 => Self::SYSTEM_COLORS[code as usize],
+            232..=255 => {
+                // the gray ramp has no hue to match, so it goes to whichever
+                // of black and white it contrasts less with
+                let luminance = Self::relative_luminance(Self::code_to_rgb(code));
+                let to_black = (luminance + 0.05) / 0.05;
+                let to_white = 1.05 / (luminance + 0.05);
+                if to_black < to_white {
+                    Color::Black
+                } else {
+                    Color::White
+                }
+            }
+            _ => {
+                let (r, g, b) = Self::code_to_rgb(code);
+                Self::rgb_to_basic(r, g, b)
+            }
+        }
+    }
+
+    /// Downgrades the color to the closest color representable at the given support level.
+    ///
+    /// - `TrueColor` keeps the color as is.
+    /// - `Color256` converts RGB and HEX colors to `Color::Color256`.
+    /// - `Basic` converts RGB, HEX and 256 colors to one of the basic ANSI colors.
+    /// - `NoColor` returns `Color::Empty`.
+    ///
+    /// Below `TrueColor`, 256 color codes of the system colors (0-15) are routed to their
+    /// predefined color, since the basic sequences are understood by more terminals.
+    ///
+    /// Colors that can not be resolved (e.g. an invalid HEX code) become `Color::Empty`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColorSupport};
+    ///
+    /// let orange = Color::RGB(255, 128, 0);
+    /// assert_eq!(orange.downgrade(ColorSupport::TrueColor), orange);
+    /// assert_eq!(orange.downgrade(ColorSupport::Color256), Color::Color256(208));
+    /// assert_eq!(orange.downgrade(ColorSupport::NoColor), Color::Empty);
+    /// ```
+    pub fn downgrade(self, support: ColorSupport) -> Color {
+        match (support, self) {
+            (_, Color::Empty) | (ColorSupport::TrueColor, _) => self,
+            (ColorSupport::NoColor, _) => Color::Empty,
+            (_, Color::Color256(code)) if code < 16 => Self::SYSTEM_COLORS[code as usize],
+            (ColorSupport::Basic, Color::Color256(code)) => Self::code_256_to_basic(code),
+            (ColorSupport::Color256, Color::RGB(..) | Color::RGBA(..) | Color::HEX(_)) => {
+                match self.to_rgb() {
+                    Ok((r, g, b)) => Color::Color256(Self::rgb_to_256(r, g, b)),
+                    Err(_) => Color::Empty,
+                }
+            }
+            (ColorSupport::Basic, Color::RGB(..) | Color::RGBA(..) | Color::HEX(_)) => {
+                match self.to_rgb() {
+                    Ok((r, g, b)) => Self::rgb_to_basic(r, g, b),
+                    Err(_) => Color::Empty,
+                }
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns the escape sequence selecting the color as the foreground,
+    /// downgraded to the given support level instead of the one detected from the environment.
+    ///
+    /// The sequence is empty for `ColorSupport::NoColor` and colors that can not be rendered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColorSupport};
+    ///
+    /// let orange = Color::RGB(255, 128, 0);
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(orange.to_fg_for(ColorSupport::TrueColor), "\x1b[38;2;255;128;0m");
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(orange.to_fg_for(ColorSupport::Color256), "\x1b[38;5;208m");
+    /// assert_eq!(orange.to_fg_for(ColorSupport::NoColor), "");
+    /// ```
+    pub fn to_fg_for(self, support: ColorSupport) -> String {
+        Style {
+            foreground: self.downgrade(support),
+            ..Style::default()
+        }
+        .to_string()
+    }
+
+    /// Returns the escape sequence selecting the color as the background, see [`Color::to_fg_for`].
+    pub fn to_bg_for(self, support: ColorSupport) -> String {
+        Style {
+            background: self.downgrade(support),
+            ..Style::default()
+        }
+        .to_string()
+    }
+
+    /// Maps RGB samples to the best representation for the given support level.
+    ///
+    /// Every sample is converted like [`Color::downgrade`] would, so `ColorSupport::TrueColor`
+    /// keeps `Color::RGB`, `ColorSupport::Color256` uses the 256 color palette,
+    /// `ColorSupport::Basic` picks the nearest of the 8 basic colors and `ColorSupport::NoColor`
+    /// results in `Color::Empty`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColorSupport};
+    ///
+    /// let samples = [(250, 10, 10), (10, 10, 250)];
+    /// assert_eq!(
+    ///     Color::quantize_slice(&samples, ColorSupport::Basic),
+    ///     vec![Color::Red, Color::Blue]
+    /// );
+    /// ```
+    pub fn quantize_slice(samples: &[(u8, u8, u8)], support: ColorSupport) -> Vec<Color> {
+        samples
+            .iter()
+            .map(|&(r, g, b)| Color::RGB(r, g, b).downgrade(support))
+            .collect()
+    }
+
+    /// Computes the WCAG 2.1 relative luminance of an RGB color.
+    fn relative_luminance((r, g, b): (u8, u8, u8)) -> f32 {
+        let linearize = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linearize(r) + 0.7152 * linearize(g) + 0.0722 * linearize(b)
+    }
+
+    /// Returns the relative luminance of the color, from `0.0` (black) to `1.0` (white).
+    ///
+    /// The channels are linearized from sRGB and weighted with the Rec. 709 coefficients,
+    /// as in the WCAG definition used by [`Color::contrast_ratio`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    pub fn luminance(self) -> Result<f32, ColorError> {
+        Ok(Self::relative_luminance(self.to_rgb()?))
+    }
+
+    /// Returns `true` if the luminance of the color is above `0.5`.
+    ///
+    /// Dark text reads better on bright colors, and light text on the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert!(Color::BrightYellow.is_bright().unwrap());
+    /// assert!(!Color::Blue.is_bright().unwrap());
+    /// ```
+    pub fn is_bright(self) -> Result<bool, ColorError> {
+        Ok(self.luminance()? > 0.5)
+    }
+
+    /// Returns `Color::Black` or `Color::White`, whichever contrasts more with the color used as a background.
+    ///
+    /// Both contrast ratios are compared (see [`Color::contrast_ratio`]) rather than using a luminance threshold.
+    /// Colors without an RGB value (e.g. `Color::Empty`, the default background of the terminal)
+    /// return `Color::Empty`, keeping the default foreground.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::Yellow.best_text_color(), Color::Black);
+    /// assert_eq!(Color::RGB(0, 0, 128).best_text_color(), Color::White);
+    /// ```
+    pub fn best_text_color(self) -> Color {
+        let contrast = |text: Color| self.contrast_ratio(text);
+        match (contrast(Color::Black), contrast(Color::White)) {
+            (Ok(black), Ok(white)) if black > white => Color::Black,
+            (Ok(_), Ok(_)) => Color::White,
+            _ => Color::Empty,
+        }
+    }
+
+    /// Computes the WCAG 2.1 contrast ratio between two colors.
+    ///
+    /// The ratio ranges from `1.0` (no contrast) to `21.0` (black on white).
+    /// WCAG recommends at least `4.5` for normal text.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let ratio = Color::RGB(0, 0, 0).contrast_ratio(Color::RGB(255, 255, 255)).unwrap();
+    /// assert!(ratio > 20.9);
+    /// ```
+    pub fn contrast_ratio(self, other: Color) -> Result<f32, ColorError> {
+        let l1 = Self::relative_luminance(self.to_rgb()?);
+        let l2 = Self::relative_luminance(other.to_rgb()?);
+
+        let (lighter, darker) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
+        Ok((lighter + 0.05) / (darker + 0.05))
+    }
+
+    /// Adjusts the color until its contrast ratio against `bg` is at least `min_ratio`.
+    ///
+    /// The color is lightened on dark backgrounds and darkened on light ones.
+    /// If the ratio still can not be met, black or white is returned, whichever contrasts more.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let bg = Color::RGB(40, 40, 40);
+    /// let fg = Color::RGB(60, 60, 60).ensure_contrast(bg, 4.5).unwrap();
+    /// assert!(fg.contrast_ratio(bg).unwrap() >= 4.5);
+    /// ```
+    pub fn ensure_contrast(self, bg: Color, min_ratio: f32) -> Result<Color, ColorError> {
+        const STEPS: u8 = 20;
+
+        if self.contrast_ratio(bg)? >= min_ratio {
+            return Ok(self);
+        }
+
+        let black = Color::RGB(0, 0, 0);
+        let white = Color::RGB(255, 255, 255);
+        let lighten = white.contrast_ratio(bg)? >= black.contrast_ratio(bg)?;
+        let target = if lighten { 255.0 } else { 0.0 };
+
+        let (r, g, b) = self.to_rgb()?;
+        for step in 1..=STEPS {
+            let t = step as f32 / STEPS as f32;
+            let towards = |c: u8| (c as f32 + (target - c as f32) * t).round() as u8;
+
+            let candidate = Color::RGB(towards(r), towards(g), towards(b));
+            if candidate.contrast_ratio(bg)? >= min_ratio {
+                return Ok(candidate);
+            }
+        }
+
+        Ok(if lighten { white } else { black })
+    }
+
+    /// Finds the color in `palette` closest to this color.
+    ///
+    /// Colors are compared by their Euclidean distance in RGB space.
+    /// On ties, the first matching palette entry wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidOperation` if the palette is empty, or a `ColorError`
+    /// if any of the colors can not be resolved to RGB.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let palette = [Color::RGB(255, 0, 0), Color::RGB(0, 0, 255)];
+    /// let nearest = Color::RGB(200, 30, 40).nearest_in(&palette).unwrap();
+    /// assert_eq!(nearest, Color::RGB(255, 0, 0));
+    /// ```
+    pub fn nearest_in(self, palette: &[Color]) -> Result<Color, ColorError> {
+        let (r, g, b) = self.to_rgb()?;
+
+        let mut nearest: Option<(u32, Color)> = None;
+        for &candidate in palette {
+            let (pr, pg, pb) = candidate.to_rgb()?;
+            let distance = [(r, pr), (g, pg), (b, pb)]
+                .iter()
+                .map(|&(a, b)| (a as i32 - b as i32).pow(2) as u32)
+                .sum();
+
+            if nearest.is_none_or(|(best, _)| distance < best) {
+                nearest = Some((distance, candidate));
+            }
+        }
+
+        nearest
+            .map(|(_, color)| color)
+            .ok_or(ColorError::InvalidOperation("the palette is empty"))
+    }
+
+    /// Measures the distance between two colors with the given metric.
+    ///
+    /// Both colors are resolved to RGB first, see [`Color::to_rgb_tuple`].
+    /// The scale of the distance depends on the metric, so only distances measured with the same
+    /// metric can be compared.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, DistanceMetric};
+    ///
+    /// let black = Color::RGB(0, 0, 0);
+    /// let white = Color::RGB(255, 255, 255);
+    /// let delta_e = black.distance(white, DistanceMetric::DeltaE76).unwrap();
+    /// assert!((delta_e - 100.0).abs() < 0.01);
+    /// ```
+    pub fn distance(self, other: Color, metric: DistanceMetric) -> Result<f32, ColorError> {
+        let (r1, g1, b1) = self.to_rgb()?;
+        let (r2, g2, b2) = other.to_rgb()?;
+
+        let distance = match metric {
+            DistanceMetric::Euclidean => {
+                let (dr, dg, db) = (
+                    r1 as f32 - r2 as f32,
+                    g1 as f32 - g2 as f32,
+                    b1 as f32 - b2 as f32,
+                );
+                (dr * dr + dg * dg + db * db).sqrt()
+            }
+            DistanceMetric::WeightedEuclidean => {
+                let red_mean = (r1 as f32 + r2 as f32) / 2.0;
+                let (dr, dg, db) = (
+                    r1 as f32 - r2 as f32,
+                    g1 as f32 - g2 as f32,
+                    b1 as f32 - b2 as f32,
+                );
+                ((2.0 + red_mean / 256.0) * dr * dr
+                    + 4.0 * dg * dg
+                    + (2.0 + (255.0 - red_mean) / 256.0) * db * db)
+                    .sqrt()
+            }
+            DistanceMetric::DeltaE76 => {
+                let (l1, a1, b1) = Self::rgb_to_lab((r1, g1, b1));
+                let (l2, a2, b2) = Self::rgb_to_lab((r2, g2, b2));
+                ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+            }
+        };
+
+        Ok(distance)
+    }
+
+    /// Converts an sRGB channel to linear light, from `0.0` to `1.0`.
+    fn srgb_to_linear(c: u8) -> f32 {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    /// Converts sRGB values to CIELAB, with the D65 white point.
+    fn rgb_to_lab((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+        let (r, g, b) = (
+            Self::srgb_to_linear(r),
+            Self::srgb_to_linear(g),
+            Self::srgb_to_linear(b),
+        );
+
+        let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+        let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+        let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+        let f = |t: f32| {
+            const DELTA: f32 = 6.0 / 29.0;
+            if t > DELTA * DELTA * DELTA {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    /// Converts RGB values to HSL.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the hue in degrees (`0.0..360.0`), and the saturation and lightness as percentages (`0.0..=100.0`).
+    pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+        let r = r as f32 / 255.0;
+        let g = g as f32 / 255.0;
+        let b = b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        // achromatic, hue and saturation are meaningless
+        if max == min {
+            return (0.0, 0.0, l * 100.0);
+        }
+
+        let delta = max - min;
+        let s = if l > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+
+        let h = if max == r {
+            (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+
+        (h * 60.0, s * 100.0, l * 100.0)
+    }
+
+    /// Converts RGB values to HSV.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the hue in degrees (`0.0..360.0`), and the saturation and value as percentages (`0.0..=100.0`).
+    pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+        let (h, _, _) = Self::rgb_to_hsl(r, g, b);
+
+        let max = r.max(g).max(b) as f32 / 255.0;
+        let min = r.min(g).min(b) as f32 / 255.0;
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        (h, s * 100.0, max * 100.0)
+    }
+
+    /// Formats the color as a CSS style `hsl()` string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::RGB(0, 255, 0).to_hsl_string().unwrap(), "hsl(120, 100%, 50%)");
+    /// ```
+    pub fn to_hsl_string(self) -> Result<String, ColorError> {
+        let (r, g, b) = self.to_rgb()?;
+        let (h, s, l) = Self::rgb_to_hsl(r, g, b);
+        Ok(format!(
+            "hsl({}, {}%, {}%)",
+            h.round() as u16 % 360,
+            s.round(),
+            l.round()
+        ))
+    }
+
+    /// Formats the color as an `hsv()` string.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::RGB(0, 255, 0).to_hsv_string().unwrap(), "hsv(120, 100%, 100%)");
+    /// ```
+    pub fn to_hsv_string(self) -> Result<String, ColorError> {
+        let (r, g, b) = self.to_rgb()?;
+        let (h, s, v) = Self::rgb_to_hsv(r, g, b);
+        Ok(format!(
+            "hsv({}, {}%, {}%)",
+            h.round() as u16 % 360,
+            s.round(),
+            v.round()
+        ))
+    }
+
+    /// Creates an RGB color.
+    ///
+    /// Like the other plain constructors ([`Color::hex`], [`Color::hsl`] and [`Color::hsv`]),
+    /// this never looks at the terminal or the environment. Adapting the color to what the terminal
+    /// supports is done when rendering, e.g. with [`ColoredString::render_with`] or [`Color::downgrade`].
+    pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::RGB(r, g, b)
+    }
+
+    /// Creates an RGB color, failing if stdout does not support true color.
+    ///
+    /// Unlike [`Color::rgb`], this checks the terminal with [`check_color_support`].
+    /// Use [`Color::new_rgb_or_downgrade`] to fall back to the closest supported color instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`ColorError::terminal`] if the terminal does not support true color.
+    pub fn new_rgb(r: u8, g: u8, b: u8) -> Result<Color, ColorError> {
+        match check_color_support() {
+            ColorSupport::TrueColor => Ok(Color::RGB(r, g, b)),
+            available => Err(ColorError::terminal(
+                ColorSupport::TrueColor,
+                available,
+                "RGB colors are only shown as is with true color",
+            )),
+        }
+    }
+
+    /// Creates an RGB color downgraded to what stdout supports, see [`Color::downgrade`].
+    ///
+    /// This never fails: a 256 color terminal gets the closest `Color::Color256`,
+    /// a basic one the closest predefined color, and without color support `Color::Empty`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{check_color_support, Color};
+    ///
+    /// let orange = Color::new_rgb_or_downgrade(255, 128, 0);
+    /// if check_color_support().is_truecolor() {
+    ///     assert_eq!(orange, Color::RGB(255, 128, 0));
+    /// }
+    /// ```
+    pub fn new_rgb_or_downgrade(r: u8, g: u8, b: u8) -> Color {
+        Color::RGB(r, g, b).downgrade(check_color_support())
+    }
+
+    /// Creates a color of the 256 color palette.
+    ///
+    /// Every code is valid, including the system colors (0-15), which are rendered with `38;5;n`
+    /// like the others. The `Result` keeps the signature in line with the other constructors,
+    /// this never returns an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style};
+    ///
+    /// let magenta = Color::new_256(5).unwrap();
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(Style::builder().foreground(magenta).build().to_string(), "\x1b[38;5;5m");
+    /// ```
+    pub fn new_256(code: u8) -> Result<Color, ColorError> {
+        Ok(Color::Color256(code))
+    }
+
+    /// Creates an RGB color from a hexadecimal code in the `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` form.
+    ///
+    /// Unlike `Color::HEX`, the code is validated right away and does not need to be `'static`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidHexCode` if the code can not be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::hex("#ff8000").unwrap(), Color::RGB(255, 128, 0));
+    /// assert!(Color::hex("ff8000").is_err());
+    /// ```
+    pub fn hex(code: &str) -> Result<Color, ColorError> {
+        Self::hex_to_rgb(code)
+            .map(|(r, g, b)| Color::RGB(r, g, b))
+            .ok_or_else(|| ColorError::InvalidHexCode(code.to_string()))
+    }
+
+    /// Creates an RGB color from HSL values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidColorValue` if the hue is outside of `0.0..=360.0`,
+    /// or the saturation or lightness is outside of `0.0..=100.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::hsl(120.0, 100.0, 50.0).unwrap(), Color::RGB(0, 255, 0));
+    /// assert!(Color::hsl(120.0, 150.0, 50.0).is_err());
+    /// ```
+    pub fn hsl(h: f32, s: f32, l: f32) -> Result<Color, ColorError> {
+        Self::validate_components(h, s, l, "lightness")?;
+        let (r, g, b) = Self::hsl_to_rgb(h, s, l);
+        Ok(Color::RGB(r, g, b))
+    }
+
+    /// Creates an RGB color from HSV values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidColorValue` if the hue is outside of `0.0..=360.0`,
+    /// or the saturation or value is outside of `0.0..=100.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::hsv(240.0, 100.0, 100.0).unwrap(), Color::RGB(0, 0, 255));
+    /// ```
+    pub fn hsv(h: f32, s: f32, v: f32) -> Result<Color, ColorError> {
+        Self::validate_components(h, s, v, "value")?;
+        let (r, g, b) = Self::hsv_to_rgb(h, s, v);
+        Ok(Color::RGB(r, g, b))
+    }
+
+    /// Checks the hue is within `0.0..=360.0` and the two percentages within `0.0..=100.0`.
+    fn validate_components(h: f32, s: f32, third: f32, third_name: &str) -> Result<(), ColorError> {
+        if !(0.0..=360.0).contains(&h) {
+            return Err(ColorError::out_of_range("hue", h, "0..=360"));
+        }
+        for (name, value) in [("saturation", s), (third_name, third)] {
+            if !(0.0..=100.0).contains(&value) {
+                return Err(ColorError::out_of_range(name, value, "0..=100"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts HSV values to RGB.
+    ///
+    /// # Parameters
+    ///
+    /// - `h`: The hue in degrees, values outside of `0.0..360.0` wrap around.
+    /// - `s`: The saturation as a percentage, clamped to `0.0..=100.0`.
+    /// - `v`: The value as a percentage, clamped to `0.0..=100.0`.
+    pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+        let s = (s / 100.0).clamp(0.0, 1.0);
+        let v = (v / 100.0).clamp(0.0, 1.0);
+
+        // HSV and HSL share the hue, only the saturation has to be converted
+        let l = v * (1.0 - s / 2.0);
+        let sl = if l <= 0.0 || l >= 1.0 {
+            0.0
+        } else {
+            (v - l) / l.min(1.0 - l)
+        };
+
+        Self::hsl_to_rgb(h, sl * 100.0, l * 100.0)
+    }
+
+    /// Converts HSL values to RGB.
+    ///
+    /// # Parameters
+    ///
+    /// - `h`: The hue in degrees, values outside of `0.0..360.0` wrap around.
+    /// - `s`: The saturation as a percentage, clamped to `0.0..=100.0`.
+    /// - `l`: The lightness as a percentage, clamped to `0.0..=100.0`.
+    pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+        let h = h.rem_euclid(360.0);
+        let s = (s / 100.0).clamp(0.0, 1.0);
+        let l = (l / 100.0).clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        // the hue is selected in 60 degree sectors, using the un-normalized degrees
+        let sector = h / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = l - chroma / 2.0;
+        let to_u8 = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+        (to_u8(r), to_u8(g), to_u8(b))
+    }
+
+    /// Converts sRGB values to the OKLab color space.
+    fn rgb_to_oklab((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+        let (r, g, b) = (
+            Self::srgb_to_linear(r),
+            Self::srgb_to_linear(g),
+            Self::srgb_to_linear(b),
+        );
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    /// Converts OKLab values back to sRGB, clamping colors outside of the sRGB gamut.
+    fn oklab_to_rgb((l, a, b): (f32, f32, f32)) -> (u8, u8, u8) {
+        let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+        let delinearize = |c: f32| {
+            let c = if c <= 0.003_130_8 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            (c * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+
+        (
+            delinearize(4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_),
+            delinearize(-1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_),
+            delinearize(-0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_),
+        )
+    }
+
+    /// Rotates the hue of the color by the given degrees, keeping its saturation and lightness.
+    ///
+    /// Negative and large rotations wrap around the color wheel.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::RGB(255, 0, 0).rotate_hue(120).unwrap(), Color::RGB(0, 255, 0));
+    /// assert_eq!(Color::RGB(255, 0, 0).rotate_hue(-120).unwrap(), Color::RGB(0, 0, 255));
+    /// ```
+    pub fn rotate_hue(self, degrees: i16) -> Result<Color, ColorError> {
+        let (r, g, b) = self.to_rgb()?;
+        let (h, s, l) = Self::rgb_to_hsl(r, g, b);
+
+        let h = (h + degrees as f32).rem_euclid(360.0);
+        let (r, g, b) = Self::hsl_to_rgb(h, s, l);
+        Ok(Color::RGB(r, g, b))
+    }
+
+    /// Returns the analogous color scheme: the colors 30° before and after on the color wheel,
+    /// around the base color.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let [before, base, after] = Color::RGB(255, 0, 0).analogous().unwrap();
+    /// assert_eq!(before, Color::RGB(255, 0, 128));
+    /// assert_eq!(base, Color::RGB(255, 0, 0));
+    /// assert_eq!(after, Color::RGB(255, 128, 0));
+    /// ```
+    pub fn analogous(self) -> Result<[Color; 3], ColorError> {
+        Ok([
+            self.rotate_hue(-30)?,
+            self.rgb_base()?,
+            self.rotate_hue(30)?,
+        ])
+    }
+
+    /// Returns the triadic color scheme: the base color and the colors 120° and 240° from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    pub fn triadic(self) -> Result<[Color; 3], ColorError> {
+        Ok([
+            self.rgb_base()?,
+            self.rotate_hue(120)?,
+            self.rotate_hue(240)?,
+        ])
+    }
+
+    /// Returns the tetradic (square) color scheme: the base color and the colors 90°, 180° and 270° from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if the color can not be resolved to RGB (e.g. `Color::Empty`).
+    pub fn tetradic(self) -> Result<[Color; 4], ColorError> {
+        Ok([
+            self.rgb_base()?,
+            self.rotate_hue(90)?,
+            self.rotate_hue(180)?,
+            self.rotate_hue(270)?,
+        ])
+    }
+
+    /// Returns the color as `Color::RGB`, the form of the other colors of a scheme.
+    fn rgb_base(self) -> Result<Color, ColorError> {
+        let (r, g, b) = self.to_rgb()?;
+        Ok(Color::RGB(r, g, b))
+    }
+
+    /// Returns `n` evenly spaced colors from this color to `other`, including both ends.
+    ///
+    /// The colors are interpolated like [`Gradient`] (in OKLCH with the `oklch` feature),
+    /// so every step is a `Color::RGB`,
+    /// except that a single step returns this color as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if a color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let steps = Color::RGB(0, 0, 0).steps_to(Color::RGB(255, 255, 255), 3).unwrap();
+    /// # #[cfg(not(feature = "oklch"))]
+    /// assert_eq!(steps, [Color::RGB(0, 0, 0), Color::RGB(128, 128, 128), Color::RGB(255, 255, 255)]);
+    /// ```
+    pub fn steps_to(self, other: Color, n: usize) -> Result<Vec<Color>, ColorError> {
+        if n == 1 {
+            return Ok(vec![self]);
+        }
+
+        Gradient::new(vec![self, other])?.sample(n)
+    }
+
+    /// Mixes two colors by linearly interpolating their RGB values.
+    ///
+    /// When both colors are `Color::Color256`, the result is snapped back to the 256 color palette,
+    /// so it stays renderable on 256 color terminals. Otherwise a `Color::RGB` is returned.
+    ///
+    /// # Parameters
+    ///
+    /// - `other`: The color to mix with.
+    /// - `t`: The amount of `other` in the mix, clamped to `0.0..=1.0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let gray = Color::RGB(0, 0, 0).mix(Color::RGB(200, 200, 200), 0.5).unwrap();
+    /// assert_eq!(gray, Color::RGB(100, 100, 100));
+    ///
+    /// let mixed = Color::Color256(196).mix(Color::Color256(21), 0.5).unwrap();
+    /// assert!(matches!(mixed, Color::Color256(_)));
+    /// ```
+    pub fn mix(self, other: Color, t: f32) -> Result<Color, ColorError> {
+        let t = t.clamp(0.0, 1.0);
+        let (r1, g1, b1) = self.to_rgb()?;
+        let (r2, g2, b2) = other.to_rgb()?;
+
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        let (r, g, b) = (lerp(r1, r2), lerp(g1, g2), lerp(b1, b2));
+
+        match (self, other) {
+            (Color::Color256(_), Color::Color256(_)) => {
+                Ok(Color::Color256(Self::rgb_to_256(r, g, b)))
+            }
+            _ => Ok(Color::RGB(r, g, b)),
+        }
+    }
+
+    /// Interpolates between two colors in the OKLCH color space.
+    ///
+    /// Lightness and chroma are interpolated linearly and the hue along the shortest arc,
+    /// which gives the most perceptually even gradients.
+    ///
+    /// # Parameters
+    ///
+    /// - `other`: The color to interpolate towards.
+    /// - `t`: The interpolation factor, clamped to `0.0..=1.0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ColorError` if either color can not be resolved to RGB (e.g. `Color::Empty`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// let red = Color::RGB(255, 0, 0);
+    /// let blue = Color::RGB(0, 0, 255);
+    /// assert_eq!(red.interpolate_oklch(blue, 0.0).unwrap(), red);
+    /// assert_eq!(red.interpolate_oklch(blue, 1.0).unwrap(), blue);
+    /// ```
+    pub fn interpolate_oklch(self, other: Color, t: f32) -> Result<Color, ColorError> {
+        // below this chroma the hue is unreliable, so the other color's hue is used
+        const ACHROMATIC: f32 = 1e-4;
+
+        let t = t.clamp(0.0, 1.0);
+        let (l1, a1, b1) = Self::rgb_to_oklab(self.to_rgb()?);
+        let (l2, a2, b2) = Self::rgb_to_oklab(other.to_rgb()?);
+
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let mut h1 = b1.atan2(a1).to_degrees();
+        let mut h2 = b2.atan2(a2).to_degrees();
+        if c1 < ACHROMATIC {
+            h1 = h2;
+        }
+        if c2 < ACHROMATIC {
+            h2 = h1;
+        }
+
+        let delta = (h2 - h1 + 540.0).rem_euclid(360.0) - 180.0;
+        let h = (h1 + delta * t).to_radians();
+        let l = l1 + (l2 - l1) * t;
+        let c = c1 + (c2 - c1) * t;
+
+        let (r, g, b) = Self::oklab_to_rgb((l, c * h.cos(), c * h.sin()));
+        Ok(Color::RGB(r, g, b))
+    }
+
+    /// Parses a list of colors separated by commas or whitespace, collecting every error.
+    ///
+    /// Unlike parsing the entries one by one and stopping at the first failure,
+    /// this returns all the colors that could be parsed together with the index and error
+    /// of every entry that could not, which is useful for reporting all the mistakes of a config at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, ColorError};
+    ///
+    /// let (colors, errors) = Color::parse_list_collect("red, #00ff00, nope");
+    ///
+    /// assert_eq!(colors, vec![Color::Red, Color::RGB(0, 255, 0)]);
+    /// assert_eq!(errors, vec![(2, ColorError::ParseError("nope".to_string()))]);
+    /// ```
+    pub fn parse_list_collect(s: &str) -> (Vec<Color>, Vec<(usize, ColorError)>) {
+        let mut colors = Vec::new();
+        let mut errors = Vec::new();
+
+        for (index, entry) in Self::split_list(s).into_iter().enumerate() {
+            match entry.parse() {
+                Ok(color) => colors.push(color),
+                Err(err) => errors.push((index, err)),
+            }
+        }
+
+        (colors, errors)
+    }
+
+    /// Parses a CSS color function: `rgb()`, `rgba()`, `hsl()` or `hsla()`.
+    ///
+    /// The arguments may be separated by commas or whitespace. RGB channels are numbers from 0 to 255
+    /// or percentages, the hue is in degrees (with an optional `deg` unit), the saturation and lightness
+    /// are percentages with an optional `%` sign, and the alpha is a number from 0 to 1 or a percentage.
+    /// Out of range values are clamped like browsers do.
+    ///
+    /// Terminals have no transparency, so colors with an alpha are composited over white,
+    /// matching how they look on a default web page.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::ParseError` if the text is not one of the supported functions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::Color;
+    ///
+    /// assert_eq!(Color::from_css("rgb(255, 128, 0)").unwrap(), Color::RGB(255, 128, 0));
+    /// assert_eq!(Color::from_css("hsl(120deg 100% 25%)").unwrap(), Color::RGB(0, 128, 0));
+    /// assert_eq!(Color::from_css("rgba(0, 0, 0, 0.5)").unwrap(), Color::RGB(127, 127, 127));
+    /// ```
+    pub fn from_css(s: &str) -> Result<Color, ColorError> {
+        let input = s.trim();
+        let error = || ColorError::ParseError(input.to_string());
+
+        let (function, args) = input
+            .strip_suffix(')')
+            .and_then(|rest| rest.split_once('('))
+            .ok_or_else(error)?;
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+
+        let number = |arg: &str| arg.parse::<f32>().ok().filter(|value| value.is_finite());
+        let percent = |arg: &str| number(arg.strip_suffix('%').unwrap_or(arg));
+        let channel = |arg: &str| {
+            let value = match arg.strip_suffix('%') {
+                Some(pct) => number(pct)? / 100.0 * 255.0,
+                None => number(arg)?,
+            };
+            Some(value.round().clamp(0.0, 255.0) as u8)
+        };
+        let alpha = |arg: &str| {
+            let value = match arg.strip_suffix('%') {
+                Some(pct) => number(pct)? / 100.0,
+                None => number(arg)?,
+            };
+            Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+
+        let function = function.trim().to_ascii_lowercase();
+        let (rgb, args) = match function.as_str() {
+            "rgb" | "rgba" if matches!(args.len(), 3 | 4) => {
+                let rgb = (channel(args[0]), channel(args[1]), channel(args[2]));
+                match rgb {
+                    (Some(r), Some(g), Some(b)) => ((r, g, b), &args[3..]),
+                    _ => return Err(error()),
+                }
+            }
+            "hsl" | "hsla" if matches!(args.len(), 3 | 4) => {
+                let hue = number(args[0].strip_suffix("deg").unwrap_or(args[0]));
+                match (hue, percent(args[1]), percent(args[2])) {
+                    (Some(h), Some(s), Some(l)) => (Self::hsl_to_rgb(h, s, l), &args[3..]),
+                    _ => return Err(error()),
+                }
+            }
+            _ => return Err(error()),
+        };
+
+        let (r, g, b) = rgb;
+        match args.first() {
+            Some(arg) => {
+                let a = alpha(arg).ok_or_else(error)?;
+                Color::RGBA(r, g, b, a).composite_over(Color::RGB(255, 255, 255))
+            }
+            None => Ok(Color::RGB(r, g, b)),
+        }
+    }
+
+    /// Splits a list of colors at commas and whitespace outside of parentheses, skipping empty entries.
+    fn split_list(s: &str) -> Vec<&str> {
+        let mut entries = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' | ' ' | '\t' | '\n' | '\r' if depth == 0 => {
+                    entries.push(&s[start..i]);
+                    start = i + c.len_utf8();
+                }
+                _ => {}
+            }
+        }
+        entries.push(&s[start..]);
+
+        entries
+            .into_iter()
+            .filter(|entry| !entry.is_empty())
+            .collect()
+    }
+}
+
+/// Parses a color from a name, a hexadecimal code, a 256 color palette code or a CSS color function.
+///
+/// Names are case insensitive and match the predefined colors, with an optional `bright`
+/// prefix (`red`, `Bright_Red`, `bright-red`). Hexadecimal codes are parsed into `Color::RGB`,
+/// and plain numbers from 0 to 255 into `Color::Color256`. See [`Color::from_css`] for the
+/// supported functions.
+///
+/// # Example
+///
+/// ```
+/// use inksac::Color;
+///
+/// assert_eq!("bright_blue".parse::<Color>().unwrap(), Color::BrightBlue);
+/// assert_eq!("#ff8800".parse::<Color>().unwrap(), Color::RGB(255, 136, 0));
+/// assert_eq!("208".parse::<Color>().unwrap(), Color::Color256(208));
+/// assert_eq!("rgb(1, 2, 3)".parse::<Color>().unwrap(), Color::RGB(1, 2, 3));
+/// assert!("purple-ish".parse::<Color>().is_err());
+/// ```
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+
+        if input.starts_with('#') {
+            return Self::hex_to_rgb(input)
+                .map(|(r, g, b)| Color::RGB(r, g, b))
+                .ok_or_else(|| ColorError::InvalidHexCode(input.to_string()));
+        }
+        if let Ok(code) = input.parse::<u8>() {
+            return Ok(Color::Color256(code));
+        }
+        if input.ends_with(')') {
+            return Self::from_css(input);
+        }
+
+        let name: String = input
+            .chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        let (bright, base) = match name.strip_prefix("bright") {
+            Some(base) => (true, base),
+            None => (false, name.as_str()),
+        };
+        let color = match base {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            _ => return Err(ColorError::ParseError(input.to_string())),
+        };
+
+        Ok(if bright { color.to_bright() } else { color })
+    }
+}
+
+/// Adds the RGB channels of two colors, saturating at 255.
+///
+/// # Panics
+///
+/// Panics if either color can not be resolved to RGB (e.g. `Color::Empty` or an invalid HEX code).
+///
+/// # Example
+///
+/// ```
+/// use inksac::Color;
+///
+/// let sum = Color::RGB(200, 100, 0) + Color::RGB(100, 100, 100);
+/// assert_eq!(sum, Color::RGB(255, 200, 100));
+/// ```
+impl Add for Color {
+    type Output = Color;
+
+    fn add(self, rhs: Color) -> Color {
+        let (r1, g1, b1) = self.to_rgb_or_panic();
+        let (r2, g2, b2) = rhs.to_rgb_or_panic();
+        Color::RGB(
+            r1.saturating_add(r2),
+            g1.saturating_add(g2),
+            b1.saturating_add(b2),
+        )
+    }
+}
+
+/// Subtracts the RGB channels of two colors, saturating at 0.
+///
+/// # Panics
+///
+/// Panics if either color can not be resolved to RGB (e.g. `Color::Empty` or an invalid HEX code).
+///
+/// # Example
+///
+/// ```
+/// use inksac::Color;
+///
+/// let difference = Color::RGB(200, 100, 0) - Color::RGB(100, 100, 100);
+/// assert_eq!(difference, Color::RGB(100, 0, 0));
+/// ```
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(self, rhs: Color) -> Color {
+        let (r1, g1, b1) = self.to_rgb_or_panic();
+        let (r2, g2, b2) = rhs.to_rgb_or_panic();
+        Color::RGB(
+            r1.saturating_sub(r2),
+            g1.saturating_sub(g2),
+            b1.saturating_sub(b2),
+        )
+    }
+}
+
+/// Scales the RGB channels of a color, clamping the result to `0..=255`.
+///
+/// A factor below `1.0` darkens the color, a factor above `1.0` brightens it.
+///
+/// # Panics
+///
+/// Panics if the color can not be resolved to RGB (e.g. `Color::Empty` or an invalid HEX code).
+///
+/// # Example
+///
+/// ```
+/// use inksac::Color;
+///
+/// assert_eq!(Color::RGB(100, 100, 100) * 0.5, Color::RGB(50, 50, 50));
+/// ```
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, factor: f32) -> Color {
+        let (r, g, b) = self.to_rgb_or_panic();
+        let scale = |c: u8| (c as f32 * factor).round().clamp(0.0, 255.0) as u8;
+        Color::RGB(scale(r), scale(g), scale(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_to_rgb() {
+        let hex = "#ff0000";
+        let (r, g, b) = Color::hex_to_rgb(hex).unwrap();
+        assert_eq!(r, 255);
+        assert_eq!(g, 0);
+        assert_eq!(b, 0);
+    }
+
+    #[test]
+    fn test_hex_to_rgb_forms() {
+        assert_eq!(Color::hex_to_rgb("#F80"), Some((255, 136, 0)));
+        assert_eq!(Color::hex_to_rgb("#F808"), Some((255, 136, 0)));
+        assert_eq!(Color::hex_to_rgb("#FF8000FF"), Some((255, 128, 0)));
+        assert_eq!(Color::hex_to_rgb("#ff800000"), Some((255, 128, 0)));
+
+        assert_eq!(Color::hex_to_rgb("#"), None);
+        assert_eq!(Color::hex_to_rgb("#12"), None);
+        assert_eq!(Color::hex_to_rgb("#12345"), None);
+        assert_eq!(Color::hex_to_rgb("#1234567"), None);
+        assert_eq!(Color::hex_to_rgb("#GG0000"), None);
+        assert_eq!(Color::hex_to_rgb("#+f+f+f"), None);
+        assert_eq!(Color::hex_to_rgb("ff0000"), None);
+
+        assert_eq!(
+            Color::HEX("#xyz").to_rgb(),
+            Err(ColorError::InvalidHexCode("#xyz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hsl_to_rgb_sectors() {
+        assert_eq!(Color::hsl_to_rgb(0.0, 100.0, 50.0), (255, 0, 0));
+        assert_eq!(Color::hsl_to_rgb(30.0, 100.0, 50.0), (255, 128, 0));
+        assert_eq!(Color::hsl_to_rgb(90.0, 100.0, 50.0), (128, 255, 0));
+        assert_eq!(Color::hsl_to_rgb(120.0, 100.0, 50.0), (0, 255, 0));
+        assert_eq!(Color::hsl_to_rgb(210.0, 100.0, 50.0), (0, 128, 255));
+        assert_eq!(Color::hsl_to_rgb(300.0, 100.0, 50.0), (255, 0, 255));
+        assert_eq!(Color::hsl_to_rgb(360.0, 100.0, 50.0), (255, 0, 0));
+        assert_eq!(Color::hsl_to_rgb(0.0, 0.0, 50.0), (128, 128, 128));
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for h in (0..360).step_by(30) {
+            let (r, g, b) = Color::hsl_to_rgb(h as f32, 100.0, 50.0);
+            let (h2, s2, l2) = Color::rgb_to_hsl(r, g, b);
+            assert!((h2 - h as f32).abs() < 1.0, "hue {} came back as {}", h, h2);
+            assert!((s2 - 100.0).abs() < 1.0);
+            assert!((l2 - 50.0).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = Color::RGB(0, 0, 0);
+        let white = Color::RGB(255, 255, 255);
+
+        let ratio = black.contrast_ratio(white).unwrap();
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert_eq!(ratio, white.contrast_ratio(black).unwrap());
+        assert!((white.contrast_ratio(white).unwrap() - 1.0).abs() < f32::EPSILON);
+        assert!(Color::Empty.contrast_ratio(white).is_err());
+    }
+
+    #[test]
+    fn test_ensure_contrast() {
+        let bg = Color::RGB(100, 100, 100);
+        let fg = Color::RGB(120, 120, 120);
+        assert!(fg.contrast_ratio(bg).unwrap() < 4.5);
+
+        let adjusted = fg.ensure_contrast(bg, 4.5).unwrap();
+        assert!(adjusted.contrast_ratio(bg).unwrap() >= 4.5);
+
+        // already readable colors are kept as is
+        let bg = Color::RGB(255, 255, 255);
+        assert_eq!(Color::Black.ensure_contrast(bg, 4.5).unwrap(), Color::Black);
+
+        // unreachable ratios fall back to black or white
+        let fallback = Color::RGB(128, 128, 128)
+            .ensure_contrast(Color::RGB(0, 0, 0), 30.0)
+            .unwrap();
+        assert_eq!(fallback, Color::RGB(255, 255, 255));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_bright_foreground() {
+        let style = Style::builder().bright_foreground(Color::Red).build();
+        assert_eq!(style.foreground, Color::BrightRed);
+        assert_eq!(style.to_string(), "\x1b[91m");
+
+        let style = Style::builder()
+            .bright_foreground(Color::BrightBlue)
+            .build();
+        assert_eq!(style.foreground, Color::BrightBlue);
+
+        let style = Style::builder()
+            .bright_foreground(Color::RGB(1, 2, 3))
+            .build();
+        assert_eq!(style.foreground, Color::RGB(1, 2, 3));
+    }
+
+    #[test]
+    fn test_interpolate_oklch() {
+        let red = Color::RGB(255, 0, 0);
+        let blue = Color::RGB(0, 0, 255);
+
+        // the naive RGB midpoint is a dark purple (128, 0, 128), and the
+        // shortest-arc HSL midpoint is a fully saturated magenta (255, 0, 255).
+        // OKLCH keeps the lightness between both ends, landing on (186, 0, 194).
+        let Color::RGB(r, g, b) = red.interpolate_oklch(blue, 0.5).unwrap() else {
+            panic!("expected an RGB color");
+        };
+        assert!((r as i16 - 186).abs() <= 2);
+        assert!(g <= 2);
+        assert!((b as i16 - 194).abs() <= 2);
+
+        let (h, _, _) = Color::rgb_to_hsl(r, g, b);
+        assert!(h > 270.0 && h < 330.0);
+
+        assert_eq!(red.interpolate_oklch(blue, 0.0).unwrap(), red);
+        assert_eq!(red.interpolate_oklch(blue, 1.0).unwrap(), blue);
+        assert!(red.interpolate_oklch(Color::Empty, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_nearest_in() {
+        let palette = [
+            Color::RGB(0, 0, 0),
+            Color::RGB(255, 0, 0),
+            Color::RGB(0, 255, 0),
+            Color::HEX("#0000ff"),
+        ];
+
+        let nearest = Color::RGB(250, 10, 10).nearest_in(&palette).unwrap();
+        assert_eq!(nearest, Color::RGB(255, 0, 0));
+        let nearest = Color::RGB(10, 10, 200).nearest_in(&palette).unwrap();
+        assert_eq!(nearest, Color::HEX("#0000ff"));
+
+        assert_eq!(
+            Color::RGB(250, 10, 10).nearest_in(&[]),
+            Err(ColorError::InvalidOperation("the palette is empty"))
+        );
+    }
+
+    #[test]
+    fn test_rgb_to_256() {
+        assert_eq!(Color::rgb_to_256(255, 0, 0), 196);
+        assert_eq!(Color::rgb_to_256(0, 0, 255), 21);
+        assert_eq!(Color::rgb_to_256(0, 0, 0), 16);
+        assert_eq!(Color::rgb_to_256(255, 255, 255), 231);
+        assert_eq!(Color::rgb_to_256(128, 128, 128), 244);
+
+        // grays use the closest of black, the ramp and white
+        assert_eq!(Color::rgb_to_256(8, 8, 8), 232);
+        assert_eq!(Color::rgb_to_256(3, 3, 3), 16);
+        assert_eq!(Color::rgb_to_256(238, 238, 238), 255);
+        assert_eq!(Color::rgb_to_256(243, 243, 243), 255);
+        assert_eq!(Color::rgb_to_256(247, 247, 247), 231);
+        assert_eq!(Color::rgb_to_256(254, 254, 254), 231);
+
+        let levels: Vec<u8> = (0..=255)
+            .map(|v| Color::code_to_rgb(Color::rgb_to_256(v, v, v)).0)
+            .collect();
+        assert!(levels.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(Color::code_to_rgb(196), (255, 0, 0));
+        assert_eq!(Color::code_to_rgb(232), (8, 8, 8));
+        assert_eq!(Color::code_to_rgb(1), (205, 0, 0));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_render_with() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 0, 0))
+            .background(Color::HEX("#0000ff"))
+            .bold()
+            .build();
+        let text = ColoredString::new("Hello", style);
+
+        assert_eq!(text.render_with(ColorSupport::TrueColor), text.to_string());
+        assert_eq!(
+            text.render_with(ColorSupport::Color256),
+            "\x1b[38;5;196;48;5;21;1mHello\x1b[0m"
+        );
+        assert_eq!(
+            text.render_with(ColorSupport::Basic),
+            "\x1b[31;44;1mHello\x1b[0m"
+        );
+        assert_eq!(text.render_with(ColorSupport::NoColor), "Hello");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_hidden() {
+        let style = Style::builder().hidden().build();
+        assert_eq!(style.to_string(), "\x1b[8m");
+        assert_eq!(
+            ColoredString::new("secret", style).to_string(),
+            "\x1b[8msecret\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_style_is_empty() {
+        assert!(Style::default().is_empty());
+        assert!(!Style::builder().hidden().build().is_empty());
+        assert!(!Style::builder().bold().build().is_empty());
+        assert!(!Style::builder().background(Color::Red).build().is_empty());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_prefix_and_reset_sequence() {
+        let styles = [
+            Style::builder().fg(Color::Red).bg(Color::Blue).build(),
+            Style::builder()
+                .fg(Color::RGB(255, 128, 0))
+                .bold()
+                .underline()
+                .build(),
+        ];
+
+        for style in styles {
+            let manual = format!("{}{}{}", style.prefix(), "text", Style::reset_sequence());
+            assert_eq!(manual, ColoredString::new("text", style).to_string());
+        }
+
+        // an empty style skips the reset as well
+        assert_eq!(Style::default().prefix(), "");
+        assert_eq!(ColoredString::plain("text").to_string(), "text");
+    }
+
+    #[test]
+    fn test_builder_aliases() {
+        let style = Style::builder().fg(Color::Green).bg(Color::Black).build();
+        assert_eq!(style.foreground, Color::Green);
+        assert_eq!(style.background, Color::Black);
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_downgrade_system_256_codes() {
+        let text = ColoredString::new("gray", Style::builder().fg(Color::Color256(7)).build());
+        assert_eq!(
+            text.render_with(ColorSupport::Color256),
+            "\x1b[37mgray\x1b[0m"
+        );
+        assert_eq!(text.render_with(ColorSupport::Basic), "\x1b[37mgray\x1b[0m");
+
+        assert_eq!(
+            Color::Color256(9).downgrade(ColorSupport::Basic),
+            Color::BrightRed
+        );
+        assert_eq!(
+            Color::Color256(196).downgrade(ColorSupport::Color256),
+            Color::Color256(196)
+        );
+    }
+
+    #[test]
+    fn test_color_arithmetic() {
+        assert_eq!(Color::RGB(100, 100, 100) * 0.5, Color::RGB(50, 50, 50));
+        assert_eq!(Color::RGB(100, 200, 0) * 2.0, Color::RGB(200, 255, 0));
+        assert_eq!(Color::RGB(100, 100, 100) * -1.0, Color::RGB(0, 0, 0));
+        assert_eq!(
+            Color::RGB(200, 10, 0) + Color::HEX("#640a00"),
+            Color::RGB(255, 20, 0)
+        );
+        assert_eq!(
+            Color::RGB(50, 100, 150) - Color::RGB(100, 50, 100),
+            Color::RGB(0, 50, 50)
+        );
+        assert_eq!(Color::Red * 1.0, Color::RGB(205, 0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_color_arithmetic_empty() {
+        let _ = Color::Empty * 0.5;
+    }
+
+    #[test]
+    fn test_builder_rgb_and_hex() {
+        let style = Style::builder()
+            .foreground_rgb(255, 128, 0)
+            .background_rgb(0, 0, 128)
+            .build();
+        assert_eq!(style.foreground, Color::RGB(255, 128, 0));
+        assert_eq!(style.background, Color::RGB(0, 0, 128));
+
+        let hex = String::from("#00ff80");
+        let style = Style::builder()
+            .foreground_hex(&hex)
+            .background_hex("#102030")
+            .build();
+        assert_eq!(style.foreground, Color::RGB(0, 255, 128));
+        assert_eq!(style.background, Color::RGB(16, 32, 48));
+
+        let style = Style::builder().foreground_hex("not a color").build();
+        assert_eq!(style.foreground, Color::Empty);
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_styled_iter() {
+        let style = Style::builder().foreground(Color::Green).build();
+        let items = [
+            String::from("one"),
+            String::from("two"),
+            String::from("three"),
+        ];
+
+        let styled: Vec<ColoredString> = items.iter().styled(style).collect();
+        assert_eq!(styled.len(), 3);
+        assert_eq!(styled[1].string, "two");
+        assert_eq!(styled[2].to_string(), "\x1b[32mthree\x1b[0m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_styled_with() {
+        let even = Style::builder().foreground(Color::Red).build();
+        let odd = Style::builder().foreground(Color::Blue).build();
+
+        let styled: Vec<ColoredString> = ["a", "b", "c"]
+            .into_iter()
+            .styled_with(|i, _| if i % 2 == 0 { even } else { odd })
+            .collect();
+
+        assert_eq!(styled.len(), 3);
+        assert_eq!(styled[0].to_string(), "\x1b[31ma\x1b[0m");
+        assert_eq!(styled[1].to_string(), "\x1b[34mb\x1b[0m");
+        assert_eq!(styled[2].to_string(), "\x1b[31mc\x1b[0m");
+    }
+
+    #[test]
+    fn test_hsl_hsv_strings() {
+        assert_eq!(
+            Color::RGB(0, 255, 0).to_hsl_string().unwrap(),
+            "hsl(120, 100%, 50%)"
+        );
+        assert_eq!(
+            Color::RGB(0, 255, 0).to_hsv_string().unwrap(),
+            "hsv(120, 100%, 100%)"
+        );
+        assert_eq!(
+            Color::HEX("#808080").to_hsl_string().unwrap(),
+            "hsl(0, 0%, 50%)"
+        );
+        assert_eq!(
+            Color::RGB(128, 0, 0).to_hsv_string().unwrap(),
+            "hsv(0, 100%, 50%)"
+        );
+        assert!(Color::Empty.to_hsl_string().is_err());
+    }
+
+    #[test]
+    fn test_display_width() {
+        let style = Style::builder().foreground(Color::Red).bold().build();
+
+        let wide = ColoredString::new("你好", style);
+        assert_eq!(wide.display_width(), 4);
+        assert_eq!(wide.len(), 6);
+
+        let combining = ColoredString::new("a\u{0301}", style);
+        assert_eq!(combining.display_width(), 1);
+        assert_eq!(combining.len(), 3);
+
+        assert_eq!(ColoredString::new("plain", style).display_width(), 5);
+        assert!(ColoredString::new("", style).is_empty());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_lines() {
+        let style = Style::builder()
+            .foreground(Color::White)
+            .background(Color::Red)
+            .build();
+        let prefix = style.to_string();
+
+        let text = "first\nsecond".style_lines(style);
+        let lines: Vec<&str> = text.split('\n').collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], format!("{}first{}", prefix, ansi_base::RESET));
+        assert_eq!(lines[1], format!("{}second{}", prefix, ansi_base::RESET));
+
+        let owned = String::from("single").style_lines(style);
+        assert_eq!(owned, format!("{}single{}", prefix, ansi_base::RESET));
+    }
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(
+            Color::RGB(0, 0, 0)
+                .mix(Color::RGB(255, 255, 255), 0.5)
+                .unwrap(),
+            Color::RGB(128, 128, 128)
+        );
+        assert_eq!(
+            Color::RGB(10, 20, 30).mix(Color::Red, 0.0).unwrap(),
+            Color::RGB(10, 20, 30)
+        );
+
+        // red (196) and blue (21) mix to a purple in the 6x6x6 cube
+        let mixed = Color::Color256(196).mix(Color::Color256(21), 0.5).unwrap();
+        assert_eq!(mixed, Color::Color256(Color::rgb_to_256(128, 0, 128)));
+        assert_eq!(
+            Color::Color256(196).mix(Color::Color256(21), 0.0).unwrap(),
+            Color::Color256(196)
+        );
+
+        // mixed representations fall back to RGB
+        assert!(matches!(
+            Color::Color256(196).mix(Color::RGB(0, 0, 255), 0.5),
+            Ok(Color::RGB(..))
+        ));
+        assert!(Color::Empty.mix(Color::Red, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_rgba_composite() {
+        let overlay = Color::RGBA(255, 0, 0, 128);
+        assert_eq!(
+            overlay.composite_over(Color::RGB(255, 255, 255)).unwrap(),
+            Color::RGB(255, 127, 127)
+        );
+        assert_eq!(
+            Color::RGBA(10, 20, 30, 255)
+                .composite_over(Color::White)
+                .unwrap(),
+            Color::RGB(10, 20, 30)
+        );
+        assert_eq!(
+            Color::RGBA(10, 20, 30, 0)
+                .composite_over(Color::Blue)
+                .unwrap(),
+            Color::RGB(0, 0, 238)
+        );
+        assert_eq!(
+            Color::RGB(1, 2, 3).composite_over(Color::White).unwrap(),
+            Color::RGB(1, 2, 3)
+        );
+        assert!(overlay.composite_over(Color::Empty).is_err());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_rgba_renders_over_black() {
+        let style = Style::builder()
+            .foreground(Color::RGBA(200, 100, 50, 128))
+            .build();
+        assert_eq!(style.to_string(), "\x1b[38;2;100;50;25m");
+        assert_eq!(
+            Color::RGBA(200, 100, 50, 128).downgrade(ColorSupport::Color256),
+            Color::Color256(Color::rgb_to_256(100, 50, 25))
+        );
+    }
+
+    #[cfg(feature = "no-color")]
+    #[test]
+    fn test_no_color_feature() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 0, 0))
+            .background(Color::Blue)
+            .bold()
+            .underline()
+            .build();
+
+        assert_eq!(style.to_string(), "");
+        assert_eq!(ColoredString::new("plain", style).to_string(), "plain");
+        assert_eq!(
+            ColoredString::new("plain", style).render_with(ColorSupport::TrueColor),
+            "plain"
+        );
+        assert_eq!("a\nb".style_lines(style), "a\nb");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_each_line() {
+        let style = Style::builder().foreground(Color::Cyan).bold().build();
+        let prefix = style.to_string();
+
+        let text = ColoredString::new("alpha\nbeta\ngamma", style).style_each_line();
+        let lines: Vec<&str> = text.split('\n').collect();
+        assert_eq!(lines.len(), 3);
+        for (line, word) in lines.iter().zip(["alpha", "beta", "gamma"]) {
+            assert_eq!(*line, format!("{}{}{}", prefix, word, ansi_base::RESET));
+        }
+
+        // empty lines still get their own wrapping
+        let text = ColoredString::new("a\n\nb", style).style_each_line();
+        assert_eq!(
+            text.split('\n').nth(1),
+            Some(format!("{}{}", prefix, ansi_base::RESET).as_str())
+        );
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!("Red".parse::<Color>(), Ok(Color::Red));
+        assert_eq!(" bright-white ".parse::<Color>(), Ok(Color::BrightWhite));
+        assert_eq!("#0af".parse::<Color>(), Ok(Color::RGB(0, 170, 255)));
+        assert_eq!("0".parse::<Color>(), Ok(Color::Color256(0)));
+        assert_eq!(
+            "#12345".parse::<Color>(),
+            Err(ColorError::InvalidHexCode("#12345".to_string()))
+        );
+        assert_eq!(
+            "256".parse::<Color>(),
+            Err(ColorError::ParseError("256".to_string()))
+        );
+        assert_eq!(
+            "brightest".parse::<Color>(),
+            Err(ColorError::ParseError("brightest".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_list_collect() {
+        let (colors, errors) = Color::parse_list_collect("green,\n #zzz 42");
+        assert_eq!(colors, vec![Color::Green, Color::Color256(42)]);
+        assert_eq!(
+            errors,
+            vec![(1, ColorError::InvalidHexCode("#zzz".to_string()))]
+        );
+
+        let (colors, errors) = Color::parse_list_collect("");
+        assert!(colors.is_empty() && errors.is_empty());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_diff() {
+        let plain = Style::default();
+        let red = Style::builder().foreground(Color::Red).build();
+        let bold_dim = Style::builder().bold().dim().build();
+
+        assert_eq!(plain.diff(&plain), "");
+        assert_eq!(plain.diff(&red), red.to_string());
+        assert_eq!(red.diff(&plain), ansi_base::RESET_FOREGROUND);
+        assert_eq!(
+            Style::builder()
+                .background(Color::Blue)
+                .build()
+                .diff(&plain),
+            ansi_base::RESET_BACKGROUND
+        );
+
+        // dropping only dim has to reset both and bring bold back
+        let bold = Style::builder().bold().build();
+        assert_eq!(bold_dim.diff(&bold), "\x1b[22;1m");
+        assert_eq!(bold.diff(&bold_dim), ansi_base::DIM);
+
+        let underlined = Style::builder().underline().hidden().build();
+        assert_eq!(underlined.diff(&plain), "\x1b[24;28m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_map_style() {
+        let text = ColoredString::new("note", Style::builder().foreground(Color::Yellow).build());
+        assert_eq!(text.to_string(), "\x1b[33mnote\x1b[0m");
+
+        let bolded = text.map_style(|style| Style {
+            bold: true,
+            ..style
+        });
+        assert_eq!(bolded.string, "note");
+        assert_eq!(bolded.style.foreground, Color::Yellow);
+        assert_eq!(bolded.to_string(), "\x1b[33;1mnote\x1b[0m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_swap_colors() {
+        let style = Style::builder()
+            .foreground(Color::RGB(1, 2, 3))
+            .underline()
+            .build();
+        let swapped = style.swap_colors();
+
+        assert_eq!(swapped.foreground, Color::Empty);
+        assert_eq!(swapped.background, Color::RGB(1, 2, 3));
+        assert!(swapped.underline);
+        assert_eq!(swapped.to_string(), "\x1b[48;2;1;2;3;4m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_combined_sequence() {
+        let style = Style::builder()
+            .foreground(Color::Red)
+            .background(Color::Color256(208))
+            .bold()
+            .italic()
+            .underline()
+            .build();
+        let combined = style.to_string();
+        let separate = format!(
+            "\x1b[31m\x1b[48;5;208m{}{}{}",
+            ansi_base::BOLD,
+            ansi_base::ITALIC,
+            ansi_base::UNDERLINE
+        );
+
+        assert_eq!(combined, "\x1b[31;48;5;208;1;3;4m");
+        assert!(combined.len() < separate.len());
+        assert_eq!(combined.matches('\x1b').count(), 1);
+
+        let rgb = Style::builder()
+            .foreground(Color::RGB(1, 2, 3))
+            .dim()
+            .build();
+        assert_eq!(rgb.to_string(), "\x1b[38;2;1;2;3;2m");
+        assert_eq!(Style::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_rgb_inspection() {
+        assert_eq!(Color::RGB(255, 128, 0).to_rgb_tuple(), Some((255, 128, 0)));
+        assert_eq!(Color::BrightBlue.to_rgb_tuple(), Some((92, 92, 255)));
+        assert_eq!(Color::Color256(232).to_rgb_tuple(), Some((8, 8, 8)));
+        assert_eq!(Color::HEX("#nope").to_rgb_tuple(), None);
+
+        assert_eq!(Color::Red.to_hex_string().as_deref(), Some("#CD0000"));
+        assert_eq!(
+            Color::HEX("#0af").to_hex_string().as_deref(),
+            Some("#00AAFF")
+        );
+        assert_eq!(
+            Color::RGBA(255, 255, 255, 0).to_hex_string().as_deref(),
+            Some("#000000")
+        );
+        assert_eq!(Color::Empty.to_hex_string(), None);
+
+        // the hex string parses back to the same RGB value
+        let hex = Color::Cyan.to_hex_string().unwrap();
+        assert_eq!(
+            hex.parse::<Color>().unwrap().to_rgb_tuple(),
+            Color::Cyan.to_rgb_tuple()
+        );
+    }
+
+    #[test]
+    fn test_quantize_slice() {
+        let samples: Vec<(u8, u8, u8)> = (0..=255u8)
+            .step_by(15)
+            .map(|v| (v, 255 - v, v / 2))
+            .collect();
+
+        let basic = Color::quantize_slice(&samples, ColorSupport::Basic);
+        assert_eq!(basic.len(), samples.len());
+        assert!(basic
+            .iter()
+            .all(|color| Color::SYSTEM_COLORS[..8].contains(color)));
+
+        let palette = Color::quantize_slice(&samples, ColorSupport::Color256);
+        assert!(palette
+            .iter()
+            .all(|color| matches!(color, Color::Color256(_))));
+
+        assert_eq!(
+            Color::quantize_slice(&samples[..1], ColorSupport::TrueColor),
+            vec![Color::RGB(0, 255, 0)]
+        );
+        assert_eq!(
+            Color::quantize_slice(&samples[..1], ColorSupport::NoColor),
+            vec![Color::Empty]
+        );
+    }
+
+    #[test]
+    fn test_error_categories() {
+        let unsupported = ColorError::UnsupportedColorMode {
+            requested: ColorSupport::TrueColor,
+            available: ColorSupport::Basic,
+            reason: String::new(),
+        };
+        assert!(unsupported.is_recoverable());
+        assert!(ColorError::NoTerminalSupport.is_recoverable());
+        assert_eq!(
+            unsupported.to_string(),
+            "Unsupported color mode: TrueColor was requested but only Basic is available"
+        );
+
+        assert!(!ColorError::InvalidHexCode("#12".to_string()).is_recoverable());
+        assert!(!ColorError::ParseError("nope".to_string()).is_recoverable());
+        assert_eq!(
+            Color::Empty.mix(Color::Red, 0.5).unwrap_err().category(),
+            ErrorCategory::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_plain_constructors() {
+        let vars = [
+            ("NO_COLOR", Some("1")),
+            ("TERM", Some("dumb")),
+            ("COLORTERM", None),
+        ];
+        crate::env::tests::run_with_env_vars(&vars, || {
+            assert_eq!(Color::rgb(1, 2, 3), Color::RGB(1, 2, 3));
+            assert_eq!(Color::hex("#0af"), Ok(Color::RGB(0, 170, 255)));
+            assert_eq!(Color::hsl(0.0, 100.0, 50.0), Ok(Color::RGB(255, 0, 0)));
+            assert_eq!(Color::hsv(60.0, 100.0, 100.0), Ok(Color::RGB(255, 255, 0)));
+            assert_eq!(Color::hsv(0.0, 0.0, 50.0), Ok(Color::RGB(128, 128, 128)));
+        });
+
+        assert!(Color::hex("#12345").is_err());
+        assert!(Color::hsl(-1.0, 50.0, 50.0).is_err());
+        assert!(Color::hsl(f32::NAN, 50.0, 50.0).is_err());
+        assert_eq!(
+            Color::hsv(10.0, 50.0, 101.0),
+            Err(ColorError::InvalidColorValue(
+                "value 101 is outside of 0..=100".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for (r, g, b) in [(255, 128, 0), (12, 200, 90), (40, 40, 40), (0, 0, 0)] {
+            let (h, s, v) = Color::rgb_to_hsv(r, g, b);
+            assert_eq!(Color::hsv_to_rgb(h, s, v), (r, g, b));
+        }
+    }
+
+    #[test]
+    fn test_code_256_to_basic() {
+        assert_eq!(Color::code_256_to_basic(196), Color::Red);
+        assert_eq!(Color::code_256_to_basic(21), Color::Blue);
+        assert_eq!(Color::code_256_to_basic(46), Color::Green);
+        assert_eq!(Color::code_256_to_basic(4), Color::Blue);
+        assert_eq!(Color::code_256_to_basic(12), Color::BrightBlue);
+
+        // the dark end of the gray ramp is black, the light end white
+        assert_eq!(Color::code_256_to_basic(233), Color::Black);
+        assert_eq!(Color::code_256_to_basic(244), Color::White);
+        assert_eq!(Color::code_256_to_basic(255), Color::White);
+
+        assert_eq!(
+            Color::Color256(196).downgrade(ColorSupport::Basic),
+            Color::code_256_to_basic(196)
+        );
+    }
+
+    #[test]
+    fn test_rotate_hue() {
+        let orange = Color::RGB(255, 128, 0);
+        let (h, s, l) = Color::rgb_to_hsl(255, 128, 0);
+
+        for degrees in [360, -360, 720] {
+            assert_eq!(orange.rotate_hue(degrees).unwrap(), orange);
+        }
+
+        let (r, g, b) = orange.rotate_hue(-90).unwrap().to_rgb().unwrap();
+        let (rotated_h, rotated_s, rotated_l) = Color::rgb_to_hsl(r, g, b);
+        assert!((rotated_h - (h + 270.0)).abs() < 1.0);
+        assert!((rotated_s - s).abs() < 1.0 && (rotated_l - l).abs() < 1.0);
+
+        // grays have no hue to rotate
+        assert_eq!(
+            Color::RGB(90, 90, 90).rotate_hue(45).unwrap(),
+            Color::RGB(90, 90, 90)
+        );
+        assert!(Color::Empty.rotate_hue(10).is_err());
+    }
+
+    #[test]
+    fn test_style_eq_and_hash() {
+        use std::collections::HashSet;
+
+        let make = || {
+            Style::builder()
+                .foreground(Color::RGB(10, 20, 30))
+                .background(Color::Color256(236))
+                .italic()
+                .build()
+        };
+
+        let styles: HashSet<Style> = [make(), make(), Style::default()].into_iter().collect();
+        assert_eq!(styles.len(), 2);
+        assert!(styles.contains(&make()));
+
+        assert_ne!(
+            make(),
+            Style {
+                bold: true,
+                ..make()
+            }
+        );
+        assert_ne!(
+            Style::builder().foreground(Color::Red).build(),
+            Style::builder().foreground(Color::Color256(1)).build()
+        );
+    }
+
+    #[test]
+    fn test_luminance() {
+        assert!((Color::RGB(255, 255, 255).luminance().unwrap() - 1.0).abs() < 1e-4);
+        assert!(Color::RGB(0, 0, 0).luminance().unwrap().abs() < 1e-4);
+        assert!(
+            Color::HEX("#00ff00").luminance().unwrap() > Color::HEX("#ff0000").luminance().unwrap()
+        );
+
+        assert!(Color::Yellow.luminance().unwrap() > 0.5);
+        assert!(Color::Yellow.is_bright().unwrap());
+        assert!(Color::White.is_bright().unwrap());
+        assert!(!Color::Color256(240).is_bright().unwrap());
+        assert!(Color::Empty.is_bright().is_err());
+    }
+
+    #[test]
+    fn test_best_text_color() {
+        assert_eq!(Color::Yellow.best_text_color(), Color::Black);
+        assert_eq!(Color::BrightWhite.best_text_color(), Color::Black);
+        assert_eq!(Color::HEX("#000080").best_text_color(), Color::White);
+        assert_eq!(Color::Color256(17).best_text_color(), Color::White);
+        assert_eq!(Color::Empty.best_text_color(), Color::Empty);
+
+        let label = String::from("ok").on(Color::RGB(0, 0, 128));
+        assert_eq!(label.string, "ok");
+        assert_eq!(
+            label.style,
+            Style::builder()
+                .foreground(Color::White)
+                .background(Color::RGB(0, 0, 128))
+                .build()
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_alternate_display() {
+        let style = Style::builder()
+            .foreground(Color::RGB(1, 2, 3))
+            .background(Color::Red)
+            .bold()
+            .build();
+        let text = ColoredString::new("log line", style);
+
+        let colored = format!("{}", text);
+        let plain = format!("{:#}", text);
+        assert!(colored.contains('\x1b'));
+        assert!(colored.ends_with(ansi_base::RESET));
+        assert_eq!(plain, "log line");
+        assert_eq!(plain, text.to_no_style());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_repeat() {
+        let style = Style::builder()
+            .background(Color::Color256(22))
+            .bold()
+            .build();
+        let block = ColoredString::new("ab", style);
+
+        let repeated = block.repeat(4);
+        assert_eq!(repeated.string, "abababab");
+        assert_eq!(repeated.style, style);
+
+        let rendered = repeated.to_string();
+        assert_eq!(rendered.matches(&style.prefix()).count(), 1);
+        assert_eq!(rendered.matches("ab").count(), 4);
+        assert_eq!(rendered.matches(ansi_base::RESET).count(), 1);
+
+        assert!(block.repeat(0).is_empty());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_invalid_hex_renders_empty() {
+        let style = Style::builder()
+            .foreground(Color::HEX("#not-a-color"))
+            .background(Color::HEX("#12"))
+            .bold()
+            .build();
+
+        assert_eq!(style.to_string(), ansi_base::BOLD);
+        assert_eq!(
+            ColoredString::new("safe", style).to_string(),
+            format!("{}safe{}", ansi_base::BOLD, ansi_base::RESET)
+        );
+
+        let only_hex = Style::builder().foreground(Color::HEX("oops")).build();
+        assert_eq!(only_hex.to_string(), "");
+    }
+
+    #[test]
+    fn test_lines() {
+        let style = Style::builder()
+            .foreground(Color::Magenta)
+            .underline()
+            .build();
+        let text = ColoredString::new("first\nsecond\nthird", style);
+
+        let lines: Vec<ColoredString> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for (line, expected) in lines.iter().zip(["first", "second", "third"]) {
+            assert_eq!(line.string, expected);
+            assert_eq!(line.style, style);
+            assert!(line.to_string().starts_with(&style.prefix()));
+        }
+
+        assert_eq!(ColoredString::new("end\n", style).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_from_css() {
+        assert_eq!(
+            Color::from_css("rgb(255,128,0)"),
+            Ok(Color::RGB(255, 128, 0))
+        );
+        assert_eq!(
+            Color::from_css(" RGB( 0 , 50% , 100% ) "),
+            Ok(Color::RGB(0, 128, 255))
+        );
+        assert_eq!(
+            Color::from_css("rgb(300, -5, 12.4)"),
+            Ok(Color::RGB(255, 0, 12))
+        );
+
+        // alpha is composited over white
+        assert_eq!(
+            Color::from_css("rgba(255,128,0,0.5)"),
+            Ok(Color::RGB(255, 191, 127))
+        );
+        assert_eq!(
+            Color::from_css("rgba(0, 0, 0, 0%)"),
+            Ok(Color::RGB(255, 255, 255))
+        );
+        assert_eq!(
+            Color::from_css("rgb(0 0 0 / 100%)"),
+            Ok(Color::RGB(0, 0, 0))
+        );
+
+        assert_eq!(
+            Color::from_css("hsl(30, 100%, 50%)"),
+            Ok(Color::RGB(255, 128, 0))
+        );
+        assert_eq!(
+            Color::from_css("hsl(390deg, 100, 50)"),
+            Ok(Color::RGB(255, 128, 0))
+        );
+        assert_eq!(
+            Color::from_css("hsla(0, 0%, 0%, 0.5)"),
+            Ok(Color::RGB(127, 127, 127))
+        );
+
+        let (colors, errors) = Color::parse_list_collect("rgb(1, 2, 3), hsl(0, 100%, 50%)");
+        assert_eq!(colors, vec![Color::RGB(1, 2, 3), Color::RGB(255, 0, 0)]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_from_css_malformed() {
+        for input in [
+            "rgb(1, 2)",
+            "rgb(1, 2, 3, 4, 5)",
+            "rgb(a, b, c)",
+            "rgb(1, 2, 3",
+            "hsl(30, 100%)",
+            "hwb(30, 10%, 10%)",
+            "rgba(1, 2, 3, half)",
+            "rgb(NaN, 0, 0)",
+        ] {
+            assert_eq!(
+                Color::from_css(input),
+                Err(ColorError::ParseError(input.to_string()))
+            );
+        }
+        assert!("rgb(1, 2, x)".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_color_counts() {
+        let levels = [
+            (ColorSupport::NoColor, 0),
+            (ColorSupport::Basic, 16),
+            (ColorSupport::Color256, 256),
+            (ColorSupport::TrueColor, 16_777_216),
+        ];
+        for (support, count) in levels {
+            assert_eq!(support.max_colors(), count);
+            assert_eq!(ColorSupport::from_color_count(count), support);
+        }
+
+        assert_eq!(ColorSupport::from_color_count(1), ColorSupport::Basic);
+        assert_eq!(ColorSupport::from_color_count(17), ColorSupport::Color256);
+        assert_eq!(ColorSupport::from_color_count(257), ColorSupport::TrueColor);
+        assert_eq!(
+            ColorSupport::from_color_count(u32::MAX),
+            ColorSupport::TrueColor
+        );
+
+        // the ordering follows the number of colors
+        assert!(levels.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_push() {
+        let style = Style::builder().foreground(Color::Green).italic().build();
+        let mut text = ColoredString::new("", style);
+        for c in ['a', 'b', 'c'] {
+            text.push(c);
+        }
+        assert_eq!(text.string, "abc");
+
+        let rendered = text.to_string();
+        assert!(rendered.contains("abc"));
+        assert_eq!(rendered.matches(&style.prefix()).count(), 1);
+
+        text.push_str(" – def");
+        assert_eq!(text.string, "abc – def");
+        assert_eq!(text.style, style);
+    }
+
+    #[test]
+    fn test_style_prefix() {
+        let style = Style::builder()
+            .foreground(Color::HEX("#ff8000"))
+            .background(Color::Black)
+            .underline()
+            .build();
+
+        for support in [
+            ColorSupport::NoColor,
+            ColorSupport::Basic,
+            ColorSupport::Color256,
+            ColorSupport::TrueColor,
+        ] {
+            let prefix = StylePrefix::new(style, support);
+            let colored = ColoredString::new("text", style);
+            assert_eq!(prefix.wrap("text"), colored.render_with(support));
+        }
+
+        let prefix = StylePrefix::new(style, ColorSupport::TrueColor);
+        assert_eq!(prefix.as_str(), style.prefix());
+        assert_eq!(
+            prefix.wrap("text"),
+            ColoredString::new("text", style).to_string()
+        );
+        assert_eq!(
+            StylePrefix::new(Style::default(), ColorSupport::TrueColor).wrap("x"),
+            "x"
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_prefix_reuse() {
+        let style = Style::builder().foreground(Color::Cyan).bold().build();
+        let prefix = StylePrefix::new(style, ColorSupport::TrueColor);
+
+        let rows: Vec<String> = (0..10_000).map(|i| prefix.wrap(&i.to_string())).collect();
+        assert_eq!(rows.len(), 10_000);
+        assert_eq!(rows[42], format!("{}42{}", style, ansi_base::RESET));
+        assert!(rows.iter().all(|row| row.starts_with(prefix.as_str())));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_underline_color() {
+        let colored = Style::builder()
+            .underline()
+            .underline_color(Color::Color256(160))
+            .build();
+        assert_eq!(colored.to_string(), "\x1b[4;58;5;160m");
+
+        // the color needs the underline to show
+        let not_underlined = Style::builder()
+            .underline_color(Color::RGB(1, 2, 3))
+            .build();
+        assert_eq!(not_underlined.to_string(), "");
+        assert!(not_underlined.is_empty());
+        assert_eq!(ColoredString::new("x", not_underlined).to_string(), "x");
+
+        let plain_underline = Style::builder().underline().build();
+        assert!(!plain_underline.to_string().contains("58;"));
+
+        let basic = Style::builder()
+            .underline()
+            .underline_color(Color::BrightRed)
+            .build();
+        assert_eq!(basic.to_string(), "\x1b[4;58;5;9m");
+
+        let rgb = Style::builder()
+            .underline()
+            .underline_color(Color::RGB(255, 0, 0))
+            .build();
+        assert_eq!(
+            rgb.downgrade(ColorSupport::Color256).underline_color,
+            Color::Color256(196)
+        );
+        assert_eq!(rgb.downgrade(ColorSupport::Basic).to_string(), "\x1b[4m");
+
+        assert_eq!(plain_underline.diff(&rgb), "\x1b[58;2;255;0;0m");
+        assert_eq!(rgb.diff(&plain_underline), "\x1b[59m");
+        assert_eq!(rgb.diff(&not_underlined), "\x1b[24;59m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_underline_style() {
+        let curly = Style::builder()
+            .foreground(Color::Red)
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .build();
+        assert_eq!(curly.to_string(), "\x1b[31;4:3m");
+
+        let dashed = Style {
+            underline_style: UnderlineStyle::Dashed,
+            ..curly
+        };
+        assert_eq!(curly.diff(&dashed), "\x1b[4:5m");
+        assert_eq!(Style::default().diff(&dashed), "\x1b[31;4:5m");
+
+        // straight underlines keep the plain code, and the shape needs the underline
+        assert_eq!(Style::builder().underline().build().to_string(), "\x1b[4m");
+        let not_underlined = Style::builder()
+            .underline_style(UnderlineStyle::Double)
+            .build();
+        assert_eq!(not_underlined.to_string(), "");
+        assert!(not_underlined.is_empty());
+        assert_eq!(ColoredString::new("x", not_underlined).to_string(), "x");
+
+        assert_eq!(
+            curly.downgrade(ColorSupport::Color256).to_string(),
+            "\x1b[31;4m"
+        );
+        assert_eq!(
+            curly.downgrade(ColorSupport::TrueColor).underline_style,
+            UnderlineStyle::Curly
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_colored_string_deref() {
+        let text = "path/to/file.rs".styled(Style::builder().foreground(Color::Cyan).build());
+
+        fn takes_str(s: &str) -> usize {
+            s.split('/').count()
+        }
+        assert_eq!(takes_str(&text), 3);
+        assert_eq!(&*text, "path/to/file.rs");
+        assert_eq!(text.as_str(), text.to_no_style());
+        assert!(text.ends_with(".rs"));
+        assert_eq!(text.find("to"), Some(5));
+
+        // formatting still goes through the styled Display
+        assert_eq!(text.to_string(), "\x1b[36mpath/to/file.rs\x1b[0m");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_plain_colored_string() {
+        let plain = ColoredString::plain("x");
+        assert_eq!(plain.to_string(), "x");
+        assert!(!plain.to_string().contains('\x1b'));
+        assert!(plain.style.is_empty());
+
+        // the reset is only written for an actual style
+        let styled = plain.map_style(|style| Style {
+            bold: true,
+            ..style
+        });
+        assert_eq!(styled.to_string(), "\x1b[1mx\x1b[0m");
+    }
+
+    #[test]
+    fn test_set_title() {
+        assert_eq!(ansi_base::set_title("inksac"), "\x1b]0;inksac\x07");
+        assert_eq!(ansi_base::set_title(""), "\x1b]0;\x07");
+
+        let title = ansi_base::set_title("a\x07b\x1b]0;c\nd\u{9c}e");
+        assert_eq!(title, "\x1b]0;ab]0;cde\x07");
+        assert_eq!(title.matches('\x07').count(), 1);
+        assert_eq!(title.matches('\x1b').count(), 1);
+
+        let mut out = Vec::new();
+        ansi_base::write_title(&mut out, "tab\ttitle").unwrap();
+        assert_eq!(out, b"\x1b]0;tabtitle\x07");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_new_256() {
+        assert!((0..=255).all(|code| Color::new_256(code) == Ok(Color::Color256(code))));
+
+        let system = Style::builder()
+            .foreground(Color::new_256(5).unwrap())
+            .background(Color::new_256(0).unwrap())
+            .build();
+        assert_eq!(system.to_string(), "\x1b[38;5;5;48;5;0m");
+    }
+
+    #[test]
+    fn test_visible_len() {
+        let bold = Style::builder().bold().build();
+        let embedded = ColoredString::new("a \x1b[31mred\x1b[0m word", bold);
+        assert_eq!(embedded.visible_len(), "a red word".len());
+        assert!(embedded.len() > embedded.visible_len());
+
+        assert_eq!(ColoredString::new("你好", bold).visible_len(), 4);
+        assert_eq!(ColoredString::plain("").visible_len(), 0);
+    }
+
+    #[test]
+    fn test_error_helpers() {
+        let err = ColorError::out_of_range("saturation", 120, "0..=100");
+        assert_eq!(
+            err,
+            ColorError::InvalidColorValue("saturation 120 is outside of 0..=100".to_string())
+        );
+        assert_eq!(Color::hsl(0.0, 120.0, 50.0), Err(err.clone()));
+        assert!(!err.is_recoverable());
+
+        let err =
+            ColorError::conversion("RGB", "a basic color", "no palette entry is close enough");
+        assert_eq!(
+            err,
+            ColorError::InvalidColorValue(
+                "can not convert RGB to a basic color, no palette entry is close enough"
+                    .to_string()
+            )
+        );
+        assert_eq!(err.category(), ErrorCategory::InvalidInput);
+
+        let err = ColorError::terminal(
+            ColorSupport::TrueColor,
+            ColorSupport::Color256,
+            "the image preview needs every shade",
+        );
+        assert_eq!(
+            err,
+            ColorError::UnsupportedColorMode {
+                requested: ColorSupport::TrueColor,
+                available: ColorSupport::Color256,
+                reason: "the image preview needs every shade".to_string(),
+            }
+        );
+        assert!(err
+            .to_string()
+            .ends_with("only Color256 is available, the image preview needs every shade"));
+        assert!(err.clone().is_recoverable());
+
+        // the requested mode is kept for a terminal without colors
+        let err = ColorError::terminal(ColorSupport::Basic, ColorSupport::NoColor, "status colors");
+        assert!(matches!(
+            err,
+            ColorError::UnsupportedColorMode {
+                requested: ColorSupport::Basic,
+                available: ColorSupport::NoColor,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("status colors"));
+    }
+
+    #[test]
+    fn test_gradient_bg() {
+        let (start, end) = (Color::RGB(255, 255, 0), Color::RGB(0, 0, 128));
+        let line = ColoredString::gradient_bg("header", start, end).unwrap();
+        let segments = line.segments();
+
+        assert_eq!(segments.len(), 6);
+        assert_eq!(segments[0].style.background, start);
+        assert_eq!(segments[5].style.background, end);
+        assert_eq!(segments[0].style.foreground, Color::Black);
+        assert_eq!(segments[5].style.foreground, Color::White);
+        assert_eq!(
+            segments
+                .iter()
+                .map(ColoredString::as_str)
+                .collect::<String>(),
+            "header"
+        );
+
+        let single = ColoredString::gradient_bg("x", start, end).unwrap();
+        assert_eq!(single.segments()[0].style.background, start);
+        assert!(ColoredString::gradient_bg("", start, end)
+            .unwrap()
+            .segments()
+            .is_empty());
+        assert!(ColoredString::gradient_bg("ab", Color::Empty, end).is_err());
+    }
+
+    #[test]
+    fn test_style_is_visible() {
+        let style = |fg, bg| Style::builder().foreground(fg).background(bg).build();
+
+        assert!(!style(Color::Red, Color::Red).is_visible().unwrap());
+        assert!(!style(Color::Red, Color::RGB(205, 0, 0))
+            .is_visible()
+            .unwrap());
+        assert!(!style(Color::RGB(30, 30, 30), Color::RGB(34, 34, 34))
+            .is_visible()
+            .unwrap());
+        assert!(style(Color::White, Color::Black).is_visible().unwrap());
+        assert!(style(Color::Red, Color::Empty).is_visible().unwrap());
+        assert!(style(Color::Empty, Color::Blue).is_visible().unwrap());
+        assert!(style(Color::HEX("#xyz"), Color::Black)
+            .is_visible()
+            .is_err());
+    }
+
+    #[test]
+    fn test_rendered_len() {
+        let styles = [
+            Style::default(),
+            Style::builder().foreground(Color::Red).build(),
+            Style::builder()
+                .foreground(Color::RGB(255, 128, 0))
+                .background(Color::Color256(236))
+                .bold()
+                .italic()
+                .build(),
+            Style::builder()
+                .underline()
+                .underline_style(UnderlineStyle::Curly)
+                .underline_color(Color::HEX("#00ff00"))
+                .build(),
+        ];
+
+        for style in styles {
+            for text in ["", "plain", "你好"] {
+                let colored = text.styled(style);
+                assert_eq!(colored.rendered_len(), colored.to_string().len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_sgr_codes() {
+        assert_eq!(Color::from_fg_code(31), Some(Color::Red));
+        assert_eq!(Color::from_fg_code(91), Some(Color::BrightRed));
+        assert_eq!(Color::from_fg_code(99), None);
+        assert_eq!(Color::from_fg_code(38), None);
+        assert_eq!(Color::from_bg_code(47), Some(Color::White));
+        assert_eq!(Color::from_bg_code(107), Some(Color::BrightWhite));
+        assert_eq!(Color::from_bg_code(31), None);
+        assert_eq!(Color::from_bg_code(5), None);
+
+        // every predefined color maps back from its own code
+        for color in Color::SYSTEM_COLORS {
+            let fg = color.sgr_params(false).unwrap().parse().unwrap();
+            let bg = color.sgr_params(true).unwrap().parse().unwrap();
+            assert_eq!(Color::from_fg_code(fg), Some(color));
+            assert_eq!(Color::from_bg_code(bg), Some(color));
+        }
+    }
+
+    #[test]
+    fn test_trim_keeps_style() {
+        let style = Style::builder().foreground(Color::Red).italic().build();
+        let text = ColoredString::new("  hi  ", style);
+
+        assert_eq!(text.trim().string, "hi");
+        assert_eq!(text.trim_start().string, "hi  ");
+        assert_eq!(text.trim_end().string, "  hi");
+        for trimmed in [text.trim(), text.trim_start(), text.trim_end()] {
+            assert_eq!(trimmed.style, style);
+        }
+
+        let blank = ColoredString::new(" \t\n", style).trim();
+        assert!(blank.is_empty());
+        assert_eq!(blank.style, style);
+    }
+
+    #[test]
+    fn test_rgb_for_terminal() {
+        let forced = |level| {
+            [
+                ("NO_COLOR", None),
+                ("CLICOLOR_FORCE", None),
+                ("FORCE_COLOR", Some(level)),
+                ("TERM", None),
+                ("COLORTERM", None),
+            ]
+        };
+
+        crate::env::tests::run_with_env_vars(&forced("2"), || {
+            assert_eq!(
+                Color::new_rgb_or_downgrade(255, 128, 0),
+                Color::Color256(208)
+            );
+            assert_eq!(
+                Color::new_rgb(255, 128, 0),
+                Err(ColorError::terminal(
+                    ColorSupport::TrueColor,
+                    ColorSupport::Color256,
+                    "RGB colors are only shown as is with true color",
+                ))
+            );
+        });
+        crate::env::tests::run_with_env_vars(&forced("1"), || {
+            assert_eq!(Color::new_rgb_or_downgrade(250, 10, 10), Color::Red);
+        });
+        crate::env::tests::run_with_env_vars(&forced("3"), || {
+            assert_eq!(Color::new_rgb(1, 2, 3), Ok(Color::RGB(1, 2, 3)));
+            assert_eq!(Color::new_rgb_or_downgrade(1, 2, 3), Color::RGB(1, 2, 3));
+        });
+        crate::env::tests::run_with_env_vars(&forced("0"), || {
+            assert_eq!(Color::new_rgb_or_downgrade(1, 2, 3), Color::Empty);
+            assert!(matches!(
+                Color::new_rgb(1, 2, 3),
+                Err(ColorError::UnsupportedColorMode {
+                    available: ColorSupport::NoColor,
+                    ..
+                })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_style_merge() {
+        let base = Style::builder()
+            .foreground(Color::Red)
+            .background(Color::Black)
+            .bold()
+            .build();
+
+        let cleared = base.merge(PartialStyle::new().bold(false));
+        assert!(!cleared.bold);
+        assert_eq!(cleared.foreground, Color::Red);
+
+        assert_eq!(base.merge(PartialStyle::new()), base);
+
+        let layered = base
+            .merge(PartialStyle::new().background(Color::Empty).underline(true))
+            .merge(PartialStyle::new().underline_style(UnderlineStyle::Dotted));
+        assert_eq!(
+            layered,
+            Style::builder()
+                .foreground(Color::Red)
+                .bold()
+                .underline()
+                .underline_style(UnderlineStyle::Dotted)
+                .build()
+        );
+
+        assert_eq!(Style::default().merge(base.to_partial()), base);
+        assert_eq!(base.merge(Style::default().to_partial()), Style::default());
+    }
+
+    #[test]
+    fn test_color_schemes() {
+        let base = Color::RGB(200, 80, 40);
+        let hue = |color: Color| {
+            let (r, g, b) = color.to_rgb().unwrap();
+            Color::rgb_to_hsl(r, g, b).0
+        };
+        let offset = |color: Color| (hue(color) - hue(base)).rem_euclid(360.0);
+        let assert_offsets = |colors: &[Color], expected: &[f32]| {
+            for (color, expected) in colors.iter().zip(expected) {
+                let diff = (offset(*color) - expected).abs();
+                assert!(diff.min(360.0 - diff) < 1.5, "{:?}", color);
+            }
+        };
+
+        let analogous = base.analogous().unwrap();
+        assert_eq!(analogous[1], base);
+        assert_offsets(&analogous, &[330.0, 0.0, 30.0]);
+
+        let triadic = base.triadic().unwrap();
+        assert_eq!(triadic[0], base);
+        assert_offsets(&triadic, &[0.0, 120.0, 240.0]);
+
+        assert_offsets(&base.tetradic().unwrap(), &[0.0, 90.0, 180.0, 270.0]);
+
+        assert_eq!(Color::Blue.triadic().unwrap()[0], Color::RGB(0, 0, 238));
+        assert!(Color::Empty.analogous().is_err());
+        assert!(Color::HEX("#nope").tetradic().is_err());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_explicit_support_rendering() {
+        let text = "status".styled(
+            Style::builder()
+                .foreground(Color::RGB(255, 128, 0))
+                .background(Color::RGB(20, 20, 60))
+                .build(),
+        );
+        let supports = [
+            ColorSupport::NoColor,
+            ColorSupport::Basic,
+            ColorSupport::Color256,
+            ColorSupport::TrueColor,
+        ];
+
+        let rendered: Vec<String> = supports.iter().map(|&s| text.render_with(s)).collect();
+        assert_eq!(
+            rendered,
+            [
+                "status",
+                "\x1b[33;40mstatus\x1b[0m",
+                "\x1b[38;5;208;48;5;17mstatus\x1b[0m",
+                "\x1b[38;2;255;128;0;48;2;20;20;60mstatus\x1b[0m",
+            ]
+        );
+
+        let fg: Vec<String> = supports.iter().map(|&s| Color::Red.to_fg_for(s)).collect();
+        assert_eq!(fg, ["", "\x1b[31m", "\x1b[31m", "\x1b[31m"]);
+        assert_eq!(
+            Color::Color256(17).to_bg_for(ColorSupport::Basic),
+            "\x1b[40m"
+        );
+    }
+
+    #[test]
+    fn test_split_keeps_style() {
+        let style = Style::builder().foreground(Color::Green).bold().build();
+        let pieces: Vec<ColoredString> = ColoredString::new("a,b,c", style).split(',').collect();
+
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(
+            pieces.iter().map(ColoredString::as_str).collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        assert!(pieces.iter().all(|piece| piece.style == style));
+
+        let empty: Vec<ColoredString> = ColoredString::new(",x,", style).split(',').collect();
+        assert_eq!(empty.len(), 3);
+        assert!(empty[0].is_empty() && empty[2].is_empty());
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_wrap_keeps_style() {
+        let style = Style::builder().foreground(Color::Yellow).build();
+        let text = ColoredString::new("inksac wraps colored paragraphs neatly", style);
+
+        let lines = text.wrap(10);
+        assert_eq!(
+            lines.iter().map(ColoredString::as_str).collect::<Vec<_>>(),
+            ["inksac", "wraps", "colored", "paragraphs", "neatly"]
+        );
+        for line in &lines {
+            assert!(line.display_width() <= 10);
+            assert_eq!(line.style, style);
+        }
+        assert_eq!(lines[0].to_string(), "\x1b[33minksac\x1b[0m");
+    }
+
+    #[test]
+    fn test_color_support_checks() {
+        let checks = |support: ColorSupport| {
+            [
+                support.is_truecolor(),
+                support.is_256(),
+                support.is_basic(),
+                support.has_color(),
+            ]
+        };
+
+        assert_eq!(checks(ColorSupport::NoColor), [false, false, false, false]);
+        assert_eq!(checks(ColorSupport::Basic), [false, false, true, true]);
+        assert_eq!(checks(ColorSupport::Color256), [false, true, false, true]);
+        assert_eq!(checks(ColorSupport::TrueColor), [true, false, false, true]);
+    }
+
+    #[test]
+    fn test_steps_to() {
+        let (black, white) = (Color::RGB(0, 0, 0), Color::RGB(255, 255, 255));
+
+        #[cfg(not(feature = "oklch"))]
+        assert_eq!(
+            black.steps_to(white, 3).unwrap(),
+            [black, Color::RGB(128, 128, 128), white]
+        );
+        assert_eq!(black.steps_to(white, 2).unwrap(), [black, white]);
+        assert_eq!(Color::Red.steps_to(white, 1).unwrap(), [Color::Red]);
+        assert!(black.steps_to(white, 0).unwrap().is_empty());
+
+        let steps = Color::Red.steps_to(Color::Blue, 5).unwrap();
+        assert_eq!(steps[0], Color::RGB(205, 0, 0));
+        assert_eq!(steps[4], Color::RGB(0, 0, 238));
+        assert!(Color::Empty.steps_to(white, 4).is_err());
+    }
+
+    #[test]
+    fn test_restyle_range() {
+        let base = Style::builder().foreground(Color::Green).build();
+        let highlight = Style::builder()
+            .background(Color::Yellow)
+            .underline()
+            .build();
+
+        let line = ColoredString::new("abcdef", base)
+            .restyle_range(2..4, highlight)
+            .unwrap();
+        let segments = line.segments();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(
+            segments
+                .iter()
+                .map(ColoredString::as_str)
+                .collect::<Vec<_>>(),
+            ["ab", "cd", "ef"]
+        );
+        assert_eq!(segments[0].style, base);
+        assert_eq!(segments[1].style, base.overlay(highlight));
+        assert_eq!(segments[2].style, base);
+
+        let whole = ColoredString::new("abc", base).restyle_range(0..3, highlight);
+        assert_eq!(whole.unwrap().segments().len(), 1);
+
+        let text = ColoredString::new("你好", base);
+        assert!(text.clone().restyle_range(0..3, highlight).is_ok());
+        assert!(text.clone().restyle_range(1..3, highlight).is_err());
+        assert!(text.restyle_range(3..7, highlight).is_err());
+    }
+
+    #[test]
+    fn test_style_overlay() {
+        let base = Style::builder()
+            .foreground(Color::Red)
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .build();
+
+        assert_eq!(base.overlay(Style::default()), base);
+        assert_eq!(Style::default().overlay(base), base);
+        assert_eq!(
+            base.overlay(Style::builder().italic().build()),
+            Style {
+                italic: true,
+                ..base
+            }
+        );
+        assert_eq!(
+            base.overlay(Style::builder().underline().build())
+                .underline_style,
+            UnderlineStyle::Straight
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_explicit_defaults() {
+        let bold = Style::builder().bold().build();
+        assert_eq!(bold.to_string(), "\x1b[1m");
+        assert_eq!(
+            bold.with_explicit_defaults(true).to_string(),
+            "\x1b[39;49;1m"
+        );
+
+        let red = Style::builder().foreground(Color::Red).build();
+        assert_eq!(red.to_string(), "\x1b[31m");
+        assert_eq!(red.with_explicit_defaults(true).to_string(), "\x1b[31;49m");
+
+        let full = Style::builder()
+            .foreground(Color::Red)
+            .background(Color::Blue)
+            .build();
+        assert_eq!(
+            full.with_explicit_defaults(true).to_string(),
+            full.to_string()
+        );
+
+        // an otherwise empty style still writes nothing
+        let empty = Style::default().with_explicit_defaults(true);
+        assert_eq!(empty.to_string(), "");
+        assert_eq!(ColoredString::new("x", empty).to_string(), "x");
+        assert!(
+            !bold
+                .with_explicit_defaults(true)
+                .with_explicit_defaults(false)
+                .explicit_defaults
+        );
+    }
+
+    #[test]
+    fn test_palette_256() {
+        let palette = Color::palette_256();
+        assert_eq!(palette[0], (0, 0, 0));
+        assert_eq!(palette[15], (255, 255, 255));
+        assert_eq!(palette[16], (0, 0, 0));
+        assert_eq!(palette[196], (255, 0, 0));
+        assert_eq!(palette[231], (255, 255, 255));
+        assert_eq!(palette[232], (8, 8, 8));
+        assert_eq!(palette[255], (238, 238, 238));
+
+        for (code, color) in Color::SYSTEM_COLORS.iter().enumerate() {
+            assert_eq!(color.to_rgb(), Ok(palette[code]));
+        }
+        assert!((0..=255).all(|code| Color::Color256(code).to_rgb() == Ok(palette[code as usize])));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_char() {
+        let style = Style::builder().foreground(Color::Magenta).bold().build();
+
+        let x = 'x'.styled(style);
+        assert_eq!(x.string, "x");
+        assert_eq!(x.to_string(), "\x1b[35;1mx\x1b[0m");
+        assert_eq!('é'.styled(style).string, "é");
+        assert_eq!('🎉'.on(Color::Black).display_width(), 2);
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_apply() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 128, 0))
+            .underline()
+            .build();
+        let apply = |vars: &[(&str, Option<&str>)]| {
+            crate::env::tests::run_with_env_vars(vars, || {
+                let mut out = Vec::new();
+                style.apply(&mut out, "text").unwrap();
+                String::from_utf8(out).unwrap()
+            })
+        };
+
+        assert_eq!(
+            apply(&[("NO_COLOR", None), ("FORCE_COLOR", Some("3"))]),
+            "\x1b[38;2;255;128;0;4mtext\x1b[0m"
+        );
+        assert_eq!(
+            apply(&[
+                ("NO_COLOR", None),
+                ("FORCE_COLOR", Some("2")),
+                ("COLORTERM", None),
+                ("TERM", None)
+            ]),
+            "\x1b[38;5;208;4mtext\x1b[0m"
+        );
+        assert_eq!(
+            apply(&[("NO_COLOR", Some("1")), ("FORCE_COLOR", None)]),
+            "text"
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_style_mut() {
+        let mut text = "cell".styled(Style::builder().foreground(Color::Red).build());
+        assert_eq!(text.to_string(), "\x1b[31mcell\x1b[0m");
+
+        text.style_mut().foreground = Color::Green;
+        text.style_mut().underline = true;
+        assert_eq!(text.to_string(), "\x1b[32;4mcell\x1b[0m");
+        assert_eq!(
+            text.current_style(),
+            Style::builder()
+                .foreground(Color::Green)
+                .underline()
+                .build()
+        );
+
+        *text.style_mut() = Style::default();
+        assert_eq!(text.to_string(), "cell");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_sgr() {
+        assert_eq!(ansi_base::sgr(&[0]), ansi_base::RESET);
+        assert_eq!(ansi_base::sgr(&[1]), ansi_base::BOLD);
+        assert_eq!(ansi_base::sgr(&[49]), ansi_base::RESET_BACKGROUND);
+        assert_eq!(ansi_base::sgr(&[]), "");
+        assert_eq!(
+            ansi_base::sgr(&[38, 2, 255, 128, 0]),
+            Color::RGB(255, 128, 0).to_fg_for(ColorSupport::TrueColor)
+        );
+        assert_eq!(
+            ansi_base::sgr(&[48, 5, 208]),
+            Color::Color256(208).to_bg_for(ColorSupport::Color256)
+        );
+        assert_eq!(
+            ansi_base::sgr(&[103]),
+            Color::BrightYellow.to_bg_for(ColorSupport::Basic)
+        );
+    }
+
+    #[test]
+    fn test_grapheme_count() {
+        let family = ColoredString::new("👨\u{200D}👩\u{200D}👧", Style::default());
+        assert_eq!(family.grapheme_count(), 1);
+        assert!(family.len() > family.display_width());
+        assert_eq!(family.display_width(), 6);
+
+        let accented = "cafe\u{301}".styled(Style::builder().bold().build());
+        assert_eq!(accented.len(), 6);
+        assert_eq!(accented.display_width(), 4);
+        assert_eq!(accented.grapheme_count(), 4);
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_reset_only_when_styled() {
+        let plain = ColoredString::new("plain text", Style::default()).to_string();
+        assert_eq!(plain.bytes().filter(|&b| b == b'\x1b').count(), 0);
+        assert_eq!(format!("{}", "x".styled(Style::default())), "x");
+
+        let styled = "x".styled(Style::builder().italic().build()).to_string();
+        assert!(styled.ends_with(Style::reset_sequence()));
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_swatch() {
+        let truecolor = [("NO_COLOR", None), ("FORCE_COLOR", Some("3"))];
+        let (fg, bg) = crate::env::tests::run_with_env_vars(&truecolor, || {
+            (
+                ColoredString::swatch(Color::RGB(255, 128, 0), 3).to_string(),
+                ColoredString::swatch_bg(Color::Green, 2).to_string(),
+            )
+        });
+        assert_eq!(fg, "\x1b[38;2;255;128;0m███\x1b[0m");
+        assert_eq!(bg, "\x1b[42m  \x1b[0m");
+
+        let fallback = [
+            ("NO_COLOR", None),
+            ("FORCE_COLOR", Some("2")),
+            ("COLORTERM", None),
+            ("TERM", None),
+        ];
+        let fg = crate::env::tests::run_with_env_vars(&fallback, || {
+            ColoredString::swatch(Color::RGB(255, 128, 0), 1).to_string()
+        });
+        assert_eq!(fg, "\x1b[38;5;208m█\x1b[0m");
+    }
+
+    #[test]
+    fn test_u32_packing() {
+        let color = Color::from_u32(0xFF8000);
+        assert_eq!(color.to_rgb_tuple(), Some((255, 128, 0)));
+        assert_eq!(color.to_u32(), Some(0xFF8000));
+        assert_eq!(Color::from_u32(0xFF000000), Color::RGB(0, 0, 0));
+        assert_eq!(Color::HEX("#0a0b0c").to_u32(), Some(0x0A0B0C));
+        assert_eq!(Color::HEX("#zz").to_u32(), None);
+    }
+
+    #[test]
+    fn test_color_distance() {
+        let metrics = [
+            DistanceMetric::Euclidean,
+            DistanceMetric::WeightedEuclidean,
+            DistanceMetric::DeltaE76,
+        ];
+        for metric in metrics {
+            assert_eq!(Color::Red.distance(Color::Red, metric), Ok(0.0));
+            assert_eq!(
+                Color::HEX("#ff8000").distance(Color::RGB(255, 128, 0), metric),
+                Ok(0.0)
+            );
+            assert!(Color::Empty.distance(Color::Red, metric).is_err());
+        }
+
+        let white = Color::RGB(255, 255, 255);
+        let euclidean = Color::RGB(0, 0, 0)
+            .distance(white, DistanceMetric::Euclidean)
+            .unwrap();
+        assert!((euclidean - 441.67).abs() < 0.01);
+
+        // the same RGB step, but the eye is less sensitive to blue than to green
+        let base = Color::RGB(100, 100, 100);
+        let distance = |other, metric| base.distance(other, metric).unwrap();
+        let bluer = Color::RGB(100, 100, 160);
+        let greener = Color::RGB(100, 160, 100);
+        assert_eq!(
+            distance(bluer, DistanceMetric::Euclidean),
+            distance(greener, DistanceMetric::Euclidean)
+        );
+        assert!(
+            distance(bluer, DistanceMetric::WeightedEuclidean)
+                < distance(greener, DistanceMetric::WeightedEuclidean)
+        );
+        assert!(
+            distance(bluer, DistanceMetric::DeltaE76) < distance(greener, DistanceMetric::DeltaE76)
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_cow_storage() {
+        static TEXT: &str = "static text";
+        let style = Style::builder().foreground(Color::Cyan).build();
+
+        let mut borrowed = ColoredString::from_static(TEXT, style);
+        assert!(matches!(borrowed.string, Cow::Borrowed(_)));
+        assert_eq!(borrowed.as_str().as_ptr(), TEXT.as_ptr());
+        assert_eq!(borrowed.len(), TEXT.len());
+        assert_eq!(borrowed.display_width(), 11);
+        assert_eq!(borrowed.to_no_style(), TEXT);
+        assert_eq!(
+            borrowed.to_string(),
+            ColoredString::new(TEXT, style).to_string()
+        );
+
+        // appending copies the text on the first write
+        borrowed.push_str("!");
+        assert!(matches!(borrowed.string, Cow::Owned(_)));
+        assert_eq!(borrowed.as_str(), "static text!");
+
+        let owned = String::from("owned");
+        let pointer = owned.as_ptr();
+        let styled = owned.styled(style);
+        assert_eq!(styled.as_str().as_ptr(), pointer);
+        assert_eq!(
+            ColoredString::from_cow(Cow::Owned("cow".to_string()), style).to_string(),
+            "\x1b[36mcow\x1b[0m"
+        );
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_colorize() {
+        let fg = colorize("text", Color::Red);
+        let expected = "text".styled(Style::builder().foreground(Color::Red).build());
+        assert_eq!(fg.as_str(), expected.as_str());
+        assert_eq!(fg.style, expected.style);
+        assert_eq!(fg.to_string(), expected.to_string());
+
+        let bg = colorize_bg(String::from("text"), Color::RGB(0, 0, 64));
+        let expected = "text".styled(Style::builder().background(Color::RGB(0, 0, 64)).build());
+        assert_eq!(bg.as_str(), expected.as_str());
+        assert_eq!(bg.style, expected.style);
+        assert_eq!(bg.to_string(), "\x1b[48;2;0;0;64mtext\x1b[0m");
     }
 }
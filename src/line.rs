@@ -0,0 +1,183 @@
+//! A line made of several styled segments, such as a status line.
+
+use std::fmt::{self, Write};
+
+use crate::{ColoredString, Style};
+
+/// A line of styled segments and plain text
+///
+/// Every styled segment is closed with its own reset, so the plain text in between
+/// (e.g. separators) is never affected by the surrounding styles.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Style, StyledLine, Stylish};
+///
+/// let green = Style::builder().foreground(Color::Green).build();
+/// let line = StyledLine::new()
+///     .push("ok".styled(green))
+///     .push_str(" 3 files");
+///
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(line.to_string(), "\x1b[32mok\x1b[0m 3 files");
+/// assert_eq!(line.display_width(), 10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StyledLine {
+    segments: Vec<ColoredString>,
+}
+
+impl StyledLine {
+    /// Creates an empty line.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Joins the segments with an unstyled separator between each of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Style, StyledLine, Stylish};
+    ///
+    /// let bold = Style::builder().bold().build();
+    /// let line = StyledLine::join(["a".styled(bold), "b".styled(bold)], ", ");
+    ///
+    /// # #[cfg(not(feature = "no-color"))]
+    /// assert_eq!(line.to_string(), "\x1b[1ma\x1b[0m, \x1b[1mb\x1b[0m");
+    /// ```
+    pub fn join(segments: impl IntoIterator<Item = ColoredString>, sep: &str) -> Self {
+        let mut line = Self::new();
+        for (index, segment) in segments.into_iter().enumerate() {
+            if index > 0 {
+                line = line.push_str(sep);
+            }
+            line = line.push(segment);
+        }
+        line
+    }
+
+    /// Appends a styled segment.
+    pub fn push(mut self, segment: ColoredString) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Appends unstyled text.
+    pub fn push_str(self, text: &str) -> Self {
+        self.push(ColoredString::new(text, Style::default()))
+    }
+
+    /// Returns the segments of the line, unstyled text has an empty style.
+    pub fn segments(&self) -> &[ColoredString] {
+        &self.segments
+    }
+
+    /// Returns the number of terminal columns the text of the line takes.
+    pub fn display_width(&self) -> usize {
+        self.segments.iter().map(ColoredString::display_width).sum()
+    }
+}
+
+impl fmt::Display for StyledLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.segments {
+            if segment.style.is_empty() {
+                write!(f, "{}", segment.string)?;
+            } else {
+                write!(f, "{}", segment)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders every segment into a single `String`, with an unstyled separator between them.
+///
+/// The buffer is sized up front, so printing a large table takes one allocation and one write
+/// instead of one per segment. Each styled segment is closed with its own reset.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{render_all, Color, ColoredString, Style, Stylish};
+///
+/// let red = Style::builder().foreground(Color::Red).build();
+/// let cells = ["a".styled(red), ColoredString::plain("b")];
+///
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(render_all(&cells, " | "), "\x1b[31ma\x1b[0m | b");
+/// ```
+pub fn render_all(items: &[ColoredString], sep: &str) -> String {
+    // room for a short style prefix and the reset around each segment
+    let capacity = items.iter().map(|item| item.len() + 16).sum::<usize>()
+        + sep.len() * items.len().saturating_sub(1);
+    let mut out = String::with_capacity(capacity);
+
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            out.push_str(sep);
+        }
+        // writing to a `String` can not fail
+        let _ = write!(out, "{}", item);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Stylish};
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_join_segments() {
+        let red = Style::builder().foreground(Color::Red).build();
+        let blue = Style::builder().foreground(Color::Blue).build();
+        let line = StyledLine::join(
+            ["main".styled(red), "+2".styled(blue), "utf-8".styled(red)],
+            " | ",
+        );
+
+        assert_eq!(line.segments().len(), 5);
+        assert_eq!(line.display_width(), 4 + 2 + 5 + 2 * 3);
+        assert_eq!(
+            line.to_string(),
+            format!(
+                "{r}main{reset} | {b}+2{reset} | {r}utf-8{reset}",
+                r = red,
+                b = blue,
+                reset = crate::ansi_base::RESET
+            )
+        );
+    }
+
+    #[test]
+    fn test_empty_line() {
+        assert_eq!(StyledLine::new().to_string(), "");
+        assert_eq!(StyledLine::join(Vec::new(), ", ").display_width(), 0);
+    }
+
+    #[test]
+    fn test_render_all() {
+        let items = [
+            "one".styled(Style::builder().foreground(Color::Red).build()),
+            ColoredString::plain("two"),
+            "three".styled(Style::builder().bold().underline().build()),
+        ];
+
+        let expected = items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        assert_eq!(render_all(&items, ", "), expected);
+        assert_eq!(
+            render_all(&items, ""),
+            items.iter().map(ToString::to_string).collect::<String>()
+        );
+        assert_eq!(render_all(&[], ", "), "");
+    }
+}
@@ -0,0 +1,218 @@
+//! Popular color palettes, built from their published RGB values.
+//!
+//! Every palette is a type with one constant per named color, and an `ALL` array
+//! to use with [`Color::nearest_in`] or [`Gradient::new`](crate::Gradient::new).
+//!
+//! # Example
+//!
+//! ```
+//! use inksac::palettes::{Dracula, Nord};
+//! use inksac::Color;
+//!
+//! assert_eq!(Dracula::PURPLE, Color::RGB(0xbd, 0x93, 0xf9));
+//! assert_eq!(Color::RGB(0x2f, 0x34, 0x41).nearest_in(&Nord::ALL).unwrap(), Nord::NORD0);
+//! ```
+
+use crate::Color;
+
+/// The Solarized palette by Ethan Schoonover
+///
+/// `BASE03` to `BASE3` are the background and content tones, from dark to light.
+#[derive(Debug, Clone, Copy)]
+pub struct Solarized;
+
+impl Solarized {
+    pub const BASE03: Color = Color::RGB(0x00, 0x2b, 0x36);
+    pub const BASE02: Color = Color::RGB(0x07, 0x36, 0x42);
+    pub const BASE01: Color = Color::RGB(0x58, 0x6e, 0x75);
+    pub const BASE00: Color = Color::RGB(0x65, 0x7b, 0x83);
+    pub const BASE0: Color = Color::RGB(0x83, 0x94, 0x96);
+    pub const BASE1: Color = Color::RGB(0x93, 0xa1, 0xa1);
+    pub const BASE2: Color = Color::RGB(0xee, 0xe8, 0xd5);
+    pub const BASE3: Color = Color::RGB(0xfd, 0xf6, 0xe3);
+    pub const YELLOW: Color = Color::RGB(0xb5, 0x89, 0x00);
+    pub const ORANGE: Color = Color::RGB(0xcb, 0x4b, 0x16);
+    pub const RED: Color = Color::RGB(0xdc, 0x32, 0x2f);
+    pub const MAGENTA: Color = Color::RGB(0xd3, 0x36, 0x82);
+    pub const VIOLET: Color = Color::RGB(0x6c, 0x71, 0xc4);
+    pub const BLUE: Color = Color::RGB(0x26, 0x8b, 0xd2);
+    pub const CYAN: Color = Color::RGB(0x2a, 0xa1, 0x98);
+    pub const GREEN: Color = Color::RGB(0x85, 0x99, 0x00);
+
+    /// Every color of the palette, in the order above.
+    pub const ALL: [Color; 16] = [
+        Self::BASE03,
+        Self::BASE02,
+        Self::BASE01,
+        Self::BASE00,
+        Self::BASE0,
+        Self::BASE1,
+        Self::BASE2,
+        Self::BASE3,
+        Self::YELLOW,
+        Self::ORANGE,
+        Self::RED,
+        Self::MAGENTA,
+        Self::VIOLET,
+        Self::BLUE,
+        Self::CYAN,
+        Self::GREEN,
+    ];
+}
+
+/// The Dracula palette
+#[derive(Debug, Clone, Copy)]
+pub struct Dracula;
+
+impl Dracula {
+    pub const BACKGROUND: Color = Color::RGB(0x28, 0x2a, 0x36);
+    pub const CURRENT_LINE: Color = Color::RGB(0x44, 0x47, 0x5a);
+    pub const FOREGROUND: Color = Color::RGB(0xf8, 0xf8, 0xf2);
+    pub const COMMENT: Color = Color::RGB(0x62, 0x72, 0xa4);
+    pub const CYAN: Color = Color::RGB(0x8b, 0xe9, 0xfd);
+    pub const GREEN: Color = Color::RGB(0x50, 0xfa, 0x7b);
+    pub const ORANGE: Color = Color::RGB(0xff, 0xb8, 0x6c);
+    pub const PINK: Color = Color::RGB(0xff, 0x79, 0xc6);
+    pub const PURPLE: Color = Color::RGB(0xbd, 0x93, 0xf9);
+    pub const RED: Color = Color::RGB(0xff, 0x55, 0x55);
+    pub const YELLOW: Color = Color::RGB(0xf1, 0xfa, 0x8c);
+
+    /// Every color of the palette, in the order above.
+    pub const ALL: [Color; 11] = [
+        Self::BACKGROUND,
+        Self::CURRENT_LINE,
+        Self::FOREGROUND,
+        Self::COMMENT,
+        Self::CYAN,
+        Self::GREEN,
+        Self::ORANGE,
+        Self::PINK,
+        Self::PURPLE,
+        Self::RED,
+        Self::YELLOW,
+    ];
+}
+
+/// The Nord palette by Arctic Ice Studio
+///
+/// `NORD0` to `NORD3` are the dark "Polar Night" tones, `NORD4` to `NORD6` the light "Snow Storm" tones,
+/// `NORD7` to `NORD10` the "Frost" blues and `NORD11` to `NORD15` the "Aurora" accents.
+#[derive(Debug, Clone, Copy)]
+pub struct Nord;
+
+impl Nord {
+    pub const NORD0: Color = Color::RGB(0x2e, 0x34, 0x40);
+    pub const NORD1: Color = Color::RGB(0x3b, 0x42, 0x52);
+    pub const NORD2: Color = Color::RGB(0x43, 0x4c, 0x5e);
+    pub const NORD3: Color = Color::RGB(0x4c, 0x56, 0x6a);
+    pub const NORD4: Color = Color::RGB(0xd8, 0xde, 0xe9);
+    pub const NORD5: Color = Color::RGB(0xe5, 0xe9, 0xf0);
+    pub const NORD6: Color = Color::RGB(0xec, 0xef, 0xf4);
+    pub const NORD7: Color = Color::RGB(0x8f, 0xbc, 0xbb);
+    pub const NORD8: Color = Color::RGB(0x88, 0xc0, 0xd0);
+    pub const NORD9: Color = Color::RGB(0x81, 0xa1, 0xc1);
+    pub const NORD10: Color = Color::RGB(0x5e, 0x81, 0xac);
+    pub const NORD11: Color = Color::RGB(0xbf, 0x61, 0x6a);
+    pub const NORD12: Color = Color::RGB(0xd0, 0x87, 0x70);
+    pub const NORD13: Color = Color::RGB(0xeb, 0xcb, 0x8b);
+    pub const NORD14: Color = Color::RGB(0xa3, 0xbe, 0x8c);
+    pub const NORD15: Color = Color::RGB(0xb4, 0x8e, 0xad);
+
+    /// Every color of the palette, in the order above.
+    pub const ALL: [Color; 16] = [
+        Self::NORD0,
+        Self::NORD1,
+        Self::NORD2,
+        Self::NORD3,
+        Self::NORD4,
+        Self::NORD5,
+        Self::NORD6,
+        Self::NORD7,
+        Self::NORD8,
+        Self::NORD9,
+        Self::NORD10,
+        Self::NORD11,
+        Self::NORD12,
+        Self::NORD13,
+        Self::NORD14,
+        Self::NORD15,
+    ];
+}
+
+/// The dark Gruvbox palette by Pavel Pertsev
+#[derive(Debug, Clone, Copy)]
+pub struct Gruvbox;
+
+impl Gruvbox {
+    pub const BG: Color = Color::RGB(0x28, 0x28, 0x28);
+    pub const FG: Color = Color::RGB(0xeb, 0xdb, 0xb2);
+    pub const GRAY: Color = Color::RGB(0x92, 0x83, 0x74);
+    pub const RED: Color = Color::RGB(0xcc, 0x24, 0x1d);
+    pub const GREEN: Color = Color::RGB(0x98, 0x97, 0x1a);
+    pub const YELLOW: Color = Color::RGB(0xd7, 0x99, 0x21);
+    pub const BLUE: Color = Color::RGB(0x45, 0x85, 0x88);
+    pub const PURPLE: Color = Color::RGB(0xb1, 0x62, 0x86);
+    pub const AQUA: Color = Color::RGB(0x68, 0x9d, 0x6a);
+    pub const ORANGE: Color = Color::RGB(0xd6, 0x5d, 0x0e);
+    pub const BRIGHT_RED: Color = Color::RGB(0xfb, 0x49, 0x34);
+    pub const BRIGHT_GREEN: Color = Color::RGB(0xb8, 0xbb, 0x26);
+    pub const BRIGHT_YELLOW: Color = Color::RGB(0xfa, 0xbd, 0x2f);
+    pub const BRIGHT_BLUE: Color = Color::RGB(0x83, 0xa5, 0x98);
+    pub const BRIGHT_PURPLE: Color = Color::RGB(0xd3, 0x86, 0x9b);
+    pub const BRIGHT_AQUA: Color = Color::RGB(0x8e, 0xc0, 0x7c);
+    pub const BRIGHT_ORANGE: Color = Color::RGB(0xfe, 0x80, 0x19);
+
+    /// Every color of the palette, in the order above.
+    pub const ALL: [Color; 17] = [
+        Self::BG,
+        Self::FG,
+        Self::GRAY,
+        Self::RED,
+        Self::GREEN,
+        Self::YELLOW,
+        Self::BLUE,
+        Self::PURPLE,
+        Self::AQUA,
+        Self::ORANGE,
+        Self::BRIGHT_RED,
+        Self::BRIGHT_GREEN,
+        Self::BRIGHT_YELLOW,
+        Self::BRIGHT_BLUE,
+        Self::BRIGHT_PURPLE,
+        Self::BRIGHT_AQUA,
+        Self::BRIGHT_ORANGE,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_documented_values() {
+        assert_eq!(Dracula::PURPLE.to_hex_string().as_deref(), Some("#BD93F9"));
+        assert_eq!(
+            Solarized::BASE03.to_hex_string().as_deref(),
+            Some("#002B36")
+        );
+        assert_eq!(Nord::NORD8.to_hex_string().as_deref(), Some("#88C0D0"));
+        assert_eq!(
+            Gruvbox::BRIGHT_ORANGE.to_hex_string().as_deref(),
+            Some("#FE8019")
+        );
+    }
+
+    #[test]
+    fn test_nearest_in_palette() {
+        assert_eq!(
+            Color::RGB(255, 90, 90).nearest_in(&Dracula::ALL).unwrap(),
+            Dracula::RED
+        );
+        assert_eq!(
+            Color::RGB(40, 140, 210)
+                .nearest_in(&Solarized::ALL)
+                .unwrap(),
+            Solarized::BLUE
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Parsing of text containing ANSI escape sequences back into styled segments.
+
+use crate::{Color, ColoredString, Style, UnderlineStyle};
+
+/// Parses text containing ANSI escape sequences into styled segments.
+///
+/// The SGR sequences (`ESC [ ... m`) are applied to a running style, and the text is split
+/// wherever the style changes. Basic, bright, 256 color and RGB colors are recognized, along with
+/// the bold, dim, italic, underline (including the styled underlines and underline colors)
+/// and hidden attributes and their resets.
+/// Unknown codes, other control sequences (e.g. cursor movement) and operating system commands
+/// (`ESC ] ... BEL` or `ESC ] ... ESC \`, e.g. window titles) are dropped.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{parse_ansi, Color, Style, Stylish};
+///
+/// let style = Style::builder().foreground(Color::Red).bold().build();
+/// let input = format!("plain {}", "red".styled(style));
+///
+/// let segments = parse_ansi(&input);
+/// # #[cfg(not(feature = "no-color"))]
+/// # {
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!(segments[0].string, "plain ");
+/// assert_eq!(segments[1].string, "red");
+/// assert_eq!(segments[1].style.foreground, Color::Red);
+/// assert!(segments[1].style.bold);
+/// # }
+/// ```
+pub fn parse_ansi(input: &str) -> Vec<ColoredString> {
+    let mut segments = Vec::new();
+    let mut style = Style::default();
+    let mut text = String::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('\x1b') {
+        text.push_str(&rest[..start]);
+        let sequence = match rest.as_bytes().get(start + 1) {
+            Some(b'[') => &rest[start + 2..],
+            Some(b']') => {
+                rest = skip_osc(&rest[start + 2..]);
+                continue;
+            }
+            _ => {
+                text.push('\x1b');
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+
+        // parameter and intermediate bytes, up to the final byte of the sequence
+        let Some(end) = sequence.bytes().position(|b| (0x40..=0x7e).contains(&b)) else {
+            rest = "";
+            break;
+        };
+        rest = &sequence[end + 1..];
+
+        if sequence.as_bytes()[end] != b'm' {
+            continue;
+        }
+
+        let next = apply_sgr(style, &sequence[..end]);
+        if next != style && !text.is_empty() {
+            segments.push(ColoredString::new(&text, style));
+            text.clear();
+        }
+        style = next;
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        segments.push(ColoredString::new(&text, style));
+    }
+
+    segments
+}
+
+/// Removes the ANSI escape sequences from the text, keeping only what is displayed.
+///
+/// Like [`parse_ansi`], this drops every control sequence and operating system command,
+/// not only the SGR sequences.
+///
+/// # Example
+///
+/// ```
+/// use inksac::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: failed"), "error: failed");
+/// ```
+pub fn strip_ansi(input: &str) -> String {
+    parse_ansi(input)
+        .iter()
+        .map(ColoredString::as_str)
+        .collect()
+}
+
+/// Skips the body of an operating system command, up to its `BEL` or `ESC \` terminator.
+///
+/// An unterminated command is cut off like an unterminated control sequence.
+fn skip_osc(body: &str) -> &str {
+    let bel = body.find('\x07').map(|i| i + 1);
+    let st = body.find("\x1b\\").map(|i| i + 2);
+
+    match bel.into_iter().chain(st).min() {
+        Some(end) => &body[end..],
+        None => "",
+    }
+}
+
+/// Applies the parameters of an SGR sequence (e.g. `1;38;5;208`) to the style.
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let mut codes = params.split(';');
+
+    while let Some(code) = codes.next() {
+        // sub-parameters are only used by the styled underlines, e.g. `4:3` for curly
+        if let Some((code, sub)) = code.split_once(':') {
+            if code == "4" {
+                apply_underline_style(&mut style, sub);
+            }
+            continue;
+        }
+
+        // an empty parameter is the same as 0, so `ESC [ m` resets everything
+        let code = match code {
+            "" => 0,
+            code => match code.parse::<u16>() {
+                Ok(code) => code,
+                Err(_) => continue,
+            },
+        };
+
+        match code {
+            0 => style = Style::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => {
+                style.underline = true;
+                style.underline_style = UnderlineStyle::Straight;
+            }
+            8 => style.hidden = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            28 => style.hidden = false,
+            30..=37 | 90..=97 => {
+                if let Some(color) = Color::from_fg_code(code as u8) {
+                    style.foreground = color;
+                }
+            }
+            40..=47 | 100..=107 => {
+                if let Some(color) = Color::from_bg_code(code as u8) {
+                    style.background = color;
+                }
+            }
+            38 => {
+                if let Some(color) = extended_color(&mut codes) {
+                    style.foreground = color;
+                }
+            }
+            48 => {
+                if let Some(color) = extended_color(&mut codes) {
+                    style.background = color;
+                }
+            }
+            58 => {
+                if let Some(color) = extended_color(&mut codes) {
+                    style.underline_color = color;
+                }
+            }
+            39 => style.foreground = Color::Empty,
+            49 => style.background = Color::Empty,
+            59 => style.underline_color = Color::Empty,
+            _ => {}
+        }
+    }
+
+    style
+}
+
+/// Applies the sub-parameter of a `4:n` code, where `0` turns the underline off.
+fn apply_underline_style(style: &mut Style, sub: &str) {
+    let underline_style = match sub {
+        "0" => {
+            style.underline = false;
+            return;
+        }
+        "1" => UnderlineStyle::Straight,
+        "2" => UnderlineStyle::Double,
+        "3" => UnderlineStyle::Curly,
+        "4" => UnderlineStyle::Dotted,
+        "5" => UnderlineStyle::Dashed,
+        _ => return,
+    };
+
+    style.underline = true;
+    style.underline_style = underline_style;
+}
+
+/// Reads the `5;n` or `2;r;g;b` parameters following a `38`, `48` or `58` code.
+fn extended_color<'a>(codes: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut component = || codes.next()?.parse::<u8>().ok();
+
+    match component()? {
+        5 => Some(Color::Color256(component()?)),
+        2 => Some(Color::RGB(component()?, component()?, component()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stylish;
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_round_trip() {
+        let styles = [
+            Style::builder().foreground(Color::BrightCyan).build(),
+            Style::builder()
+                .foreground(Color::RGB(255, 128, 0))
+                .background(Color::Color256(236))
+                .bold()
+                .underline()
+                .underline_color(Color::RGB(0, 0, 255))
+                .build(),
+            Style::builder()
+                .background(Color::Magenta)
+                .dim()
+                .italic()
+                .hidden()
+                .build(),
+        ];
+        let segments: Vec<ColoredString> = ["one", "two words", "3"]
+            .into_iter()
+            .zip(styles)
+            .map(|(text, style)| text.styled(style))
+            .collect();
+        let rendered: String = segments.iter().map(ToString::to_string).collect();
+
+        let parsed = parse_ansi(&rendered);
+        assert_eq!(parsed.len(), segments.len());
+        for (parsed, original) in parsed.iter().zip(&segments) {
+            assert_eq!(parsed.string, original.string);
+            assert_eq!(parsed.style, original.style);
+            assert_eq!(parsed.to_string(), original.to_string());
+        }
+
+        let line = crate::StyledLine::join(segments.clone(), " | ");
+        let parsed = parse_ansi(&line.to_string());
+        assert_eq!(parsed.len(), 5);
+        assert_eq!(parsed[1].string, " | ");
+        assert!(parsed[1].style.is_empty());
+    }
+
+    #[test]
+    fn test_transitions_and_resets() {
+        let parsed = parse_ansi("\x1b[1;31mab\x1b[22mcd\x1b[39m\x1b[44mef\x1b[mgh");
+        let texts: Vec<&str> = parsed.iter().map(|s| s.as_str()).collect();
+        assert_eq!(texts, ["ab", "cd", "ef", "gh"]);
+
+        let red = Style::builder().foreground(Color::Red).build();
+        assert_eq!(parsed[0].style, Style { bold: true, ..red });
+        assert_eq!(parsed[1].style, red);
+        assert_eq!(
+            parsed[2].style,
+            Style::builder().background(Color::Blue).build()
+        );
+        assert_eq!(parsed[3].style, Style::default());
+    }
+
+    #[test]
+    fn test_unknown_sequences() {
+        // unknown codes keep the style, other sequences are dropped
+        let parsed = parse_ansi("\x1b[31;53mred\x1b[2Kstill\x1b[999m red\x1b[38;5m");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].string, "redstill red");
+        assert_eq!(parsed[0].style.foreground, Color::Red);
+
+        // non numeric parameters are skipped instead of resetting
+        let parsed = parse_ansi("\x1b[1;?;4:9;3mtext");
+        assert_eq!(parsed[0].style, Style::builder().bold().italic().build());
+
+        // redundant sequences do not split the text
+        assert_eq!(parse_ansi("a\x1b[0mb\x1b[0m").len(), 1);
+        assert!(parse_ansi("").is_empty());
+        assert_eq!(parse_ansi("cut\x1b[31")[0].string, "cut");
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    #[test]
+    fn test_underline_styles() {
+        let curly = Style::builder()
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::RGB(255, 0, 0))
+            .build();
+        let parsed = parse_ansi(&"typo".styled(curly).to_string());
+        assert_eq!(parsed[0].style, curly);
+
+        let parsed = parse_ansi("\x1b[4:4ma\x1b[4:0mb\x1b[4mc");
+        assert_eq!(parsed[0].style.underline_style, UnderlineStyle::Dotted);
+        assert!(!parsed[1].style.underline);
+        assert_eq!(parsed[2].style, Style::builder().underline().build());
+    }
+
+    #[test]
+    fn test_operating_system_commands() {
+        let title = crate::ansi_base::set_title("hi");
+        assert_eq!(strip_ansi(&title), "");
+        assert_eq!(strip_ansi(&format!("{}done", title)), "done");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+            "link"
+        );
+
+        let parsed = parse_ansi("\x1b[31ma\x1b]0;title\x07b");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].string, "ab");
+        assert_eq!(parsed[0].style.foreground, Color::Red);
+
+        assert_eq!(strip_ansi("cut\x1b]0;no end"), "cut");
+        assert_eq!(strip_ansi("lone \x1b escape"), "lone \x1b escape");
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        let red = Style::builder().foreground(Color::Red).build();
+        let rendered = format!("{} and {}", "one".styled(red), "two".styled(red));
+        assert_eq!(strip_ansi(&rendered), "one and two");
+        assert_eq!(strip_ansi("\x1b[2Kcleared"), "cleared");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}
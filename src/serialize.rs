@@ -0,0 +1,178 @@
+//! Serialization of colors, styles and styled text with serde.
+//!
+//! `Style`, `UnderlineStyle` and `ColoredString` derive the traits, while `Color` goes through
+//! [`ColorRepr`], as the code of a `Color::HEX` can not be borrowed for `'static` when deserializing.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Color;
+
+/// The serialized form of a [`Color`], with an owned hexadecimal code
+///
+/// A `HEX` color is read back as the `Color::RGB` of its code, and an invalid code is an error.
+// the variant names are those of `Color`, which are what gets serialized
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Color")]
+enum ColorRepr {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Empty,
+    RGB(u8, u8, u8),
+    HEX(String),
+    Color256(u8),
+    RGBA(u8, u8, u8, u8),
+}
+
+impl From<Color> for ColorRepr {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => ColorRepr::Black,
+            Color::Red => ColorRepr::Red,
+            Color::Green => ColorRepr::Green,
+            Color::Yellow => ColorRepr::Yellow,
+            Color::Blue => ColorRepr::Blue,
+            Color::Magenta => ColorRepr::Magenta,
+            Color::Cyan => ColorRepr::Cyan,
+            Color::White => ColorRepr::White,
+            Color::BrightBlack => ColorRepr::BrightBlack,
+            Color::BrightRed => ColorRepr::BrightRed,
+            Color::BrightGreen => ColorRepr::BrightGreen,
+            Color::BrightYellow => ColorRepr::BrightYellow,
+            Color::BrightBlue => ColorRepr::BrightBlue,
+            Color::BrightMagenta => ColorRepr::BrightMagenta,
+            Color::BrightCyan => ColorRepr::BrightCyan,
+            Color::BrightWhite => ColorRepr::BrightWhite,
+            Color::Empty => ColorRepr::Empty,
+            Color::RGB(r, g, b) => ColorRepr::RGB(r, g, b),
+            Color::HEX(code) => ColorRepr::HEX(code.to_string()),
+            Color::Color256(code) => ColorRepr::Color256(code),
+            Color::RGBA(r, g, b, a) => ColorRepr::RGBA(r, g, b, a),
+        }
+    }
+}
+
+impl TryFrom<ColorRepr> for Color {
+    type Error = crate::ColorError;
+
+    fn try_from(repr: ColorRepr) -> Result<Self, Self::Error> {
+        Ok(match repr {
+            ColorRepr::Black => Color::Black,
+            ColorRepr::Red => Color::Red,
+            ColorRepr::Green => Color::Green,
+            ColorRepr::Yellow => Color::Yellow,
+            ColorRepr::Blue => Color::Blue,
+            ColorRepr::Magenta => Color::Magenta,
+            ColorRepr::Cyan => Color::Cyan,
+            ColorRepr::White => Color::White,
+            ColorRepr::BrightBlack => Color::BrightBlack,
+            ColorRepr::BrightRed => Color::BrightRed,
+            ColorRepr::BrightGreen => Color::BrightGreen,
+            ColorRepr::BrightYellow => Color::BrightYellow,
+            ColorRepr::BrightBlue => Color::BrightBlue,
+            ColorRepr::BrightMagenta => Color::BrightMagenta,
+            ColorRepr::BrightCyan => Color::BrightCyan,
+            ColorRepr::BrightWhite => Color::BrightWhite,
+            ColorRepr::Empty => Color::Empty,
+            ColorRepr::RGB(r, g, b) => Color::RGB(r, g, b),
+            ColorRepr::HEX(code) => Color::hex(&code)?,
+            ColorRepr::Color256(code) => Color::Color256(code),
+            ColorRepr::RGBA(r, g, b, a) => Color::RGBA(r, g, b, a),
+        })
+    }
+}
+
+/// Writes the color as an enum variant, e.g. `"Red"` or `{"RGB":[255,128,0]}` in JSON.
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ColorRepr::from(*self).serialize(serializer)
+    }
+}
+
+/// Reads the form written by `Serialize`, turning a `HEX` code into `Color::RGB`.
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Color::try_from(ColorRepr::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, ColoredString, Style, UnderlineStyle};
+
+    fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_color_round_trip() {
+        let colors = [
+            Color::Red,
+            Color::BrightCyan,
+            Color::Empty,
+            Color::RGB(255, 128, 0),
+            Color::Color256(208),
+            Color::RGBA(1, 2, 3, 128),
+        ];
+        for color in colors {
+            assert_eq!(round_trip(&color), color);
+        }
+
+        assert_eq!(serde_json::to_string(&Color::Red).unwrap(), r#""Red""#);
+        assert_eq!(
+            serde_json::to_string(&Color::RGB(255, 128, 0)).unwrap(),
+            r#"{"RGB":[255,128,0]}"#
+        );
+
+        // hex codes come back as the RGB color they stand for
+        assert_eq!(round_trip(&Color::HEX("#ff8000")), Color::RGB(255, 128, 0));
+        assert!(serde_json::from_str::<Color>(r##"{"HEX":"#zz"}"##).is_err());
+    }
+
+    #[test]
+    fn test_style_round_trip() {
+        let style = Style::builder()
+            .foreground(Color::RGB(255, 128, 0))
+            .background(Color::Blue)
+            .bold()
+            .underline()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::Color256(160))
+            .build();
+        assert_eq!(round_trip(&style), style);
+
+        // missing fields keep their default
+        let partial: Style = serde_json::from_str(r#"{"foreground":"Red","bold":true}"#).unwrap();
+        assert_eq!(
+            partial,
+            Style::builder().foreground(Color::Red).bold().build()
+        );
+    }
+
+    #[test]
+    fn test_colored_string_round_trip() {
+        let text = ColoredString::new(
+            "cached line",
+            Style::builder().foreground(Color::Green).italic().build(),
+        );
+
+        let parsed = round_trip(&text);
+        assert_eq!(parsed.as_str(), "cached line");
+        assert_eq!(parsed.style, text.style);
+        assert_eq!(parsed.to_string(), text.to_string());
+    }
+}
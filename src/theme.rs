@@ -0,0 +1,206 @@
+//! Themes mapping role names to styles, loadable from TOML or JSON.
+
+use std::collections::BTreeMap;
+
+use crate::{ColorError, ColoredString, Style};
+
+/// A set of styles looked up by role name, such as `error` or `path`
+///
+/// With the `toml` or `json` feature, a theme can be read from a file where every role is a table
+/// of [`Style`] fields, using the serde form of [`Color`](crate::Color) (e.g. `"Red"` or `{ RGB = [255, 128, 0] }`).
+/// Role names are made of ASCII letters, digits, `-`, `_` and `.`.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Style, Theme};
+///
+/// let theme = Theme::new().with_role("error", Style::builder().foreground(Color::Red).bold().build());
+///
+/// println!("{}", theme.styled("error", "file not found"));
+/// assert_eq!(theme.style("warning"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "BTreeMap<String, Style>", into = "BTreeMap<String, Style>")
+)]
+pub struct Theme {
+    roles: BTreeMap<String, Style>,
+}
+
+impl Theme {
+    /// Creates a theme without any role.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style of a role, replacing the previous one.
+    pub fn with_role(mut self, role: &str, style: Style) -> Self {
+        self.roles.insert(role.to_string(), style);
+        self
+    }
+
+    /// Returns the style of a role, or `None` if the theme does not define it.
+    pub fn style(&self, role: &str) -> Option<Style> {
+        self.roles.get(role).copied()
+    }
+
+    /// Styles the text with the style of a role, leaving it plain if the theme does not define it.
+    pub fn styled(&self, role: &str, text: &str) -> ColoredString {
+        ColoredString::new(text, self.style(role).unwrap_or_default())
+    }
+
+    /// Returns the names of the roles, in alphabetical order.
+    pub fn roles(&self) -> impl Iterator<Item = &str> {
+        self.roles.keys().map(String::as_str)
+    }
+
+    /// Reads a theme from TOML, with one table per role.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Theme};
+    ///
+    /// let theme = Theme::from_toml_str(
+    ///     r#"
+    ///     [error]
+    ///     foreground = "Red"
+    ///     bold = true
+    ///
+    ///     [path]
+    ///     foreground = { RGB = [255, 128, 0] }
+    ///     "#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(theme.style("path").unwrap().foreground, Color::RGB(255, 128, 0));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidTheme` with the location of the mistake if the text is not
+    /// valid TOML, or if a role name, a style field or a color is invalid.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Self, ColorError> {
+        toml::from_str(s).map_err(|err| ColorError::InvalidTheme(err.to_string()))
+    }
+
+    /// Reads a theme from JSON, with one object per role.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inksac::{Color, Theme};
+    ///
+    /// let theme = Theme::from_json_str(r#"{"error": {"foreground": "Red", "bold": true}}"#).unwrap();
+    ///
+    /// assert_eq!(theme.style("error").unwrap().foreground, Color::Red);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ColorError::InvalidTheme` with the location of the mistake if the text is not
+    /// valid JSON, or if a role name, a style field or a color is invalid.
+    #[cfg(feature = "json")]
+    pub fn from_json_str(s: &str) -> Result<Self, ColorError> {
+        serde_json::from_str(s).map_err(|err| ColorError::InvalidTheme(err.to_string()))
+    }
+}
+
+/// Checks that the role names are usable, for the themes read with serde.
+impl TryFrom<BTreeMap<String, Style>> for Theme {
+    type Error = ColorError;
+
+    fn try_from(roles: BTreeMap<String, Style>) -> Result<Self, Self::Error> {
+        let is_valid = |role: &str| {
+            !role.is_empty()
+                && role
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        if let Some(role) = roles.keys().find(|role| !is_valid(role)) {
+            return Err(ColorError::InvalidTheme(format!(
+                "invalid role name {:?}",
+                role
+            )));
+        }
+        Ok(Theme { roles })
+    }
+}
+
+impl From<Theme> for BTreeMap<String, Style> {
+    fn from(theme: Theme) -> Self {
+        theme.roles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_roles() {
+        let error = Style::builder().foreground(Color::Red).bold().build();
+        let theme = Theme::new()
+            .with_role("error", error)
+            .with_role("path", Style::builder().underline().build());
+
+        assert_eq!(theme.style("error"), Some(error));
+        assert_eq!(theme.style("missing"), None);
+        assert_eq!(theme.roles().collect::<Vec<_>>(), vec!["error", "path"]);
+        assert_eq!(theme.styled("missing", "plain").to_string(), "plain");
+
+        assert!(Theme::try_from(BTreeMap::from([("has space".to_string(), error)])).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml_str() {
+        let theme = Theme::from_toml_str(
+            r#"
+            [error]
+            foreground = "BrightRed"
+            bold = true
+
+            [hint]
+            foreground = { Color256 = 244 }
+            italic = true
+            "#,
+        )
+        .unwrap();
+
+        let error = Style::builder().foreground(Color::BrightRed).bold().build();
+        assert_eq!(theme.style("error"), Some(error));
+        assert_eq!(
+            theme.styled("error", "failed").to_string(),
+            ColoredString::new("failed", error).to_string()
+        );
+        assert_eq!(
+            theme.style("hint").unwrap().foreground,
+            Color::Color256(244)
+        );
+
+        // the errors point to the mistake
+        let err = Theme::from_toml_str("[error]\nforeground = \"Redd\"").unwrap_err();
+        assert!(matches!(err, ColorError::InvalidTheme(_)));
+        assert!(err.to_string().contains("Redd"), "{}", err);
+
+        let err = Theme::from_toml_str("[\"bad role\"]\nbold = true").unwrap_err();
+        assert!(err.to_string().contains("bad role"), "{}", err);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_from_json_str() {
+        let theme =
+            Theme::from_json_str(r#"{"ok": {"foreground": {"RGB": [0, 200, 0]}}}"#).unwrap();
+        assert_eq!(theme.style("ok").unwrap().foreground, Color::RGB(0, 200, 0));
+
+        let err = Theme::from_json_str(r##"{"ok": {"foreground": {"HEX": "#zz"}}}"##).unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+    }
+}
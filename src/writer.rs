@@ -0,0 +1,177 @@
+//! A writer keeping a current style across writes, like the state of a terminal.
+
+use std::io;
+
+use crate::{styling_enabled, Style};
+
+/// A writer that applies the current style to everything written
+///
+/// Only the changes between styles are written (see [`Style::diff`]), so writing many pieces
+/// of text with the same style costs no escape sequences after the first one.
+/// Flushing closes the output with a reset, so the terminal is left without any style.
+///
+/// # Example
+///
+/// ```
+/// use inksac::{Color, Style, StyledWriter};
+///
+/// let mut writer = StyledWriter::new(Vec::new());
+/// writer.set_style(Style::builder().foreground(Color::Red).build());
+/// writer.write_str("err").unwrap();
+/// writer.write_str("or").unwrap();
+/// writer.reset();
+/// writer.write_str(": failed").unwrap();
+///
+/// let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+/// # #[cfg(not(feature = "no-color"))]
+/// assert_eq!(output, "\x1b[31merror\x1b[39m: failed");
+/// ```
+#[derive(Debug)]
+pub struct StyledWriter<W: io::Write> {
+    inner: W,
+    /// The style the output is currently in.
+    current: Style,
+    /// The style to apply to the next write.
+    style: Style,
+}
+
+impl<W: io::Write> StyledWriter<W> {
+    /// Creates a writer without any style.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            current: Style::default(),
+            style: Style::default(),
+        }
+    }
+
+    /// Sets the style of the following writes.
+    ///
+    /// Nothing is written until the next [`StyledWriter::write_str`].
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// Returns the style of the following writes.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Removes the style of the following writes.
+    pub fn reset(&mut self) {
+        self.style = Style::default();
+    }
+
+    /// Writes the text with the current style.
+    pub fn write_str(&mut self, text: &str) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        if cfg!(feature = "no-color") || !styling_enabled() {
+            return self.inner.write_all(text.as_bytes());
+        }
+
+        if self.style != self.current {
+            self.inner
+                .write_all(self.current.diff(&self.style).as_bytes())?;
+            self.current = self.style;
+        }
+        self.inner.write_all(text.as_bytes())
+    }
+
+    /// Writes a reset if the output is styled, then flushes the inner writer.
+    ///
+    /// The style is kept for the following writes, and applied again by the next one.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if !self.current.is_empty() {
+            self.inner.write_all(Style::reset_sequence().as_bytes())?;
+            self.current = Style::default();
+        }
+        self.inner.flush()
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Flushes the writer and returns the inner writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+}
+
+// every test checks the written escape sequences
+#[cfg(all(test, not(feature = "no-color")))]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn output(writer: StyledWriter<Vec<u8>>) -> String {
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_same_style_writes() {
+        let red = Style::builder().foreground(Color::Red).build();
+        let mut writer = StyledWriter::new(Vec::new());
+
+        writer.set_style(red);
+        for word in ["a", "b", "c"] {
+            writer.write_str(word).unwrap();
+        }
+        writer.set_style(red);
+        writer.write_str("d").unwrap();
+
+        assert_eq!(output(writer), "\x1b[31mabcd\x1b[0m");
+    }
+
+    #[test]
+    fn test_style_changes() {
+        let bold = Style::builder().bold().build();
+        let mut writer = StyledWriter::new(Vec::new());
+
+        writer.write_str("plain ").unwrap();
+        writer.set_style(bold);
+        writer.write_str("bold ").unwrap();
+        writer.set_style(Style {
+            foreground: Color::Green,
+            ..bold
+        });
+        writer.write_str("green").unwrap();
+
+        // the style is only applied by a write
+        writer.set_style(Style::builder().italic().build());
+        writer.reset();
+        writer.write_str(".").unwrap();
+
+        assert_eq!(
+            output(writer),
+            "plain \x1b[1mbold \x1b[32mgreen\x1b[39;22m."
+        );
+    }
+
+    #[test]
+    fn test_flush_resets() {
+        let underline = Style::builder().underline().build();
+        let mut writer = StyledWriter::new(Vec::new());
+
+        writer.set_style(underline);
+        writer.write_str("a").unwrap();
+        writer.flush().unwrap();
+        writer.flush().unwrap();
+        writer.write_str("b").unwrap();
+
+        assert_eq!(writer.style(), underline);
+        assert_eq!(output(writer), "\x1b[4ma\x1b[0m\x1b[4mb\x1b[0m");
+        assert_eq!(output(StyledWriter::new(Vec::new())), "");
+
+        // an underline color alone is not shown, so there is nothing to reset
+        let mut writer = StyledWriter::new(Vec::new());
+        writer.set_style(Style::builder().underline_color(Color::Red).build());
+        writer.write_str("c").unwrap();
+        assert_eq!(output(writer), "c");
+    }
+}
@@ -0,0 +1,23 @@
+//! The reset sequence is global, so it is tested in its own process.
+
+#![cfg(not(feature = "no-color"))]
+
+use inksac::{set_reset_sequence, Color, ResetSequence, Style, StyledWriter, Stylish};
+
+#[test]
+fn test_short_reset_sequence() {
+    let text = "status".styled(Style::builder().foreground(Color::Green).build());
+    assert_eq!(text.to_string(), "\x1b[32mstatus\x1b[0m");
+
+    set_reset_sequence(ResetSequence::Short);
+    assert_eq!(Style::reset_sequence(), "\x1b[m");
+    assert_eq!(text.to_string(), "\x1b[32mstatus\x1b[m");
+
+    let mut writer = StyledWriter::new(Vec::new());
+    writer.set_style(text.style);
+    writer.write_str("a").unwrap();
+    assert_eq!(writer.into_inner().unwrap(), b"\x1b[32ma\x1b[m");
+
+    set_reset_sequence(ResetSequence::Full);
+    assert_eq!(text.to_string(), "\x1b[32mstatus\x1b[0m");
+}
@@ -0,0 +1,36 @@
+//! The styling toggle is global, so it is tested in its own process.
+
+#![cfg(not(feature = "no-color"))]
+
+use inksac::{set_styling_enabled, styling_enabled, CellGrid, Color, ColorSupport, Style, Stylish};
+
+#[test]
+fn test_styling_toggle() {
+    let style = Style::builder()
+        .foreground(Color::Green)
+        .background(Color::RGB(0, 0, 64))
+        .bold()
+        .build();
+    let text = "status".styled(style);
+    let colored = text.to_string();
+    assert!(styling_enabled());
+    assert!(colored.contains('\x1b'));
+
+    set_styling_enabled(false);
+    assert!(!styling_enabled());
+    assert_eq!(text.to_string(), "status");
+    assert_eq!(text.render_with(ColorSupport::TrueColor), "status");
+    assert_eq!("a\nb".style_lines(style), "a\nb");
+    assert_eq!(style.to_string(), "");
+
+    // the lower level renderers follow the toggle as well
+    let mut grid = CellGrid::new(2, 1);
+    grid.set(0, 0, 'o', style).unwrap();
+    grid.set(1, 0, 'k', style).unwrap();
+    assert!(!grid.render().contains('\x1b'));
+    assert_eq!(grid.render(), "ok");
+    assert!(!Style::default().diff(&style).contains('\x1b'));
+
+    set_styling_enabled(true);
+    assert_eq!(text.to_string(), colored);
+}